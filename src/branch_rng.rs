@@ -1,4 +1,5 @@
-use crate::rng::{SplitMix64, XoRoShiRo128, XorShift32};
+use crate::rng::{SplitMix64, XoRoShiRo128, XorShift128, XorShift32};
+use crate::stateful::StatefulRng;
 use rand_core::RngCore;
 
 /// Trait for branching RNGs.
@@ -9,6 +10,34 @@ pub trait BranchRng<T> {
     fn branch_rng(&mut self) -> T;
 }
 
+/// Split off an independent generator of the same type, the explicit alternative to [`Clone`].
+///
+/// Cloning an engine copies its state, so the clone draws exactly the same numbers as the
+/// original. `fork` clones and branches in one step, see [`BranchRng`], so the two go their
+/// separate ways. It is implemented for every engine that branches into its own type, and
+/// keeps working with the `strict` feature, which removes `Clone` from the engines.
+///
+/// ```rust
+/// use voxell_rng::prelude::*;
+/// use voxell_rng::rng::XoRoShiRo128;
+///
+/// let mut sparks = XoRoShiRo128::new(3);
+/// let mut smoke = sparks.fork();
+/// assert_ne!(sparks.next_u64(), smoke.next_u64());
+/// ```
+pub trait ForkRng: Sized {
+    /// Split off a new generator that won't produce the same numbers as `self`.
+    #[must_use]
+    fn fork(&mut self) -> Self;
+}
+
+impl<R: BranchRng<R>> ForkRng for R {
+    #[inline]
+    fn fork(&mut self) -> Self {
+        self.branch_rng()
+    }
+}
+
 impl BranchRng<Self> for SplitMix64 {
     #[inline]
     fn branch_rng(&mut self) -> Self {
@@ -20,8 +49,9 @@ impl BranchRng<Self> for SplitMix64 {
 impl BranchRng<Self> for XoRoShiRo128 {
     #[inline]
     fn branch_rng(&mut self) -> Self {
-        let mut other = self.clone();
-        other.long_jump();
+        // the branch takes over the current stream and `self` jumps past everything it can draw
+        let other = self.duplicate();
+        self.long_jump();
         other
     }
 }
@@ -37,3 +67,96 @@ impl BranchRng<Self> for XorShift32 {
         }
     }
 }
+
+impl BranchRng<Self> for XorShift128 {
+    #[inline]
+    fn branch_rng(&mut self) -> Self {
+        Self::new(self.next_u64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ForkRng;
+    use crate::rng::pcg_advanced::pcg_128::PcgInnerStateSetseq128;
+    use crate::rng::pcg_advanced::pcg_16::PcgInnerStateSetseq16;
+    use crate::rng::pcg_advanced::pcg_32::PcgInnerStateSetseq32;
+    use crate::rng::pcg_advanced::pcg_64::PcgInnerStateSetseq64;
+    use crate::rng::pcg_advanced::pcg_8::PcgInnerStateSetseq8;
+    use crate::rng::{Pcg128, Pcg16, Pcg32, Pcg64, Pcg8, SplitMix64, XoRoShiRo128, XorShift128, XorShift32};
+    use crate::stateful::StatefulRng;
+    use core::fmt::Debug;
+    use rand_core::RngCore;
+
+    fn assert_forks_differ<R: ForkRng + RngCore + StatefulRng>(name: &str, mut rng: R)
+    where
+        R::State: PartialEq + Debug,
+    {
+        let before = rng.state();
+        let mut first = rng.fork();
+        let mut second = rng.fork();
+        assert_ne!(rng.state(), before, "{name}: forking should move the parent forward");
+        let draws = [first.next_u64(), second.next_u64(), rng.next_u64()];
+        assert_ne!(draws[0], draws[1], "{name}: two forks should draw different numbers");
+        assert_ne!(draws[0], draws[2], "{name}: a fork should draw different numbers than its parent");
+        assert_ne!(draws[1], draws[2], "{name}: a fork should draw different numbers than its parent");
+    }
+
+    fn assert_branches_change_stream<W: Copy + PartialEq + Debug, R: ForkRng + StatefulRng<State = [W; 2]>>(name: &str, mut rng: R) {
+        let [_, inc] = rng.state();
+        let [_, first] = rng.fork().state();
+        let [_, second] = rng.fork().state();
+        assert_ne!(first, inc, "{name}: a branch should be on another stream than its parent");
+        assert_ne!(second, inc, "{name}: a branch should be on another stream than its parent");
+    }
+
+    #[test]
+    fn every_engine_forks_apart_from_its_parent() {
+        assert_forks_differ("pcg8", Pcg8::new(3));
+        assert_forks_differ("pcg16", Pcg16::new(3));
+        assert_forks_differ("pcg32", Pcg32::new(3));
+        assert_forks_differ("pcg64", Pcg64::new(3));
+        assert_forks_differ("pcg128", Pcg128::new(3));
+        assert_forks_differ("splitmix64", SplitMix64::new(3));
+        assert_forks_differ("xorshift32", XorShift32::new(3));
+        assert_forks_differ("xorshift128", XorShift128::new(3));
+        assert_forks_differ("xoroshiro128", XoRoShiRo128::new(3));
+    }
+
+    #[test]
+    fn pcg_setseq_forks_and_parent_draw_different_numbers() {
+        assert_forks_differ("setseq8", PcgInnerStateSetseq8::setseq_seeded(3, 5));
+        assert_forks_differ("setseq16", PcgInnerStateSetseq16::setseq_seeded(3, 5));
+        assert_forks_differ("setseq32", PcgInnerStateSetseq32::setseq_seeded(3, 5));
+        assert_forks_differ("setseq64", PcgInnerStateSetseq64::setseq_seeded(3, 5));
+        assert_forks_differ("setseq128", PcgInnerStateSetseq128::setseq_seeded(3, 5));
+    }
+
+    #[test]
+    fn pcg_setseq_branches_onto_other_streams() {
+        assert_branches_change_stream("setseq8", PcgInnerStateSetseq8::setseq_seeded(3, 5));
+        assert_branches_change_stream("setseq16", PcgInnerStateSetseq16::setseq_seeded(3, 5));
+        assert_branches_change_stream("setseq32", PcgInnerStateSetseq32::setseq_seeded(3, 5));
+        assert_branches_change_stream("setseq64", PcgInnerStateSetseq64::setseq_seeded(3, 5));
+        assert_branches_change_stream("setseq128", PcgInnerStateSetseq128::setseq_seeded(3, 5));
+    }
+
+    #[test]
+    fn xoroshiro128_forks_draw_different_numbers() {
+        let mut rng = XoRoShiRo128::new(3);
+        let mut first = rng.fork();
+        let mut second = rng.fork();
+        let draws = [first.next_u64(), second.next_u64(), rng.next_u64()];
+        assert_ne!(draws[0], draws[1], "two forks should draw different numbers");
+        assert_ne!(draws[0], draws[2], "a fork should draw different numbers than its parent");
+        assert_ne!(draws[1], draws[2], "a fork should draw different numbers than its parent");
+    }
+
+    #[test]
+    fn xoroshiro128_forks_from_a_zero_first_word() {
+        let mut rng = XoRoShiRo128::wrap([0, 1]);
+        let mut fork = rng.fork();
+        assert_eq!(fork.next_u64(), 1, "the fork should continue from the parent's state");
+        assert_ne!(rng.get_current_state(), [0, 1], "the parent should jump ahead");
+    }
+}
@@ -0,0 +1,269 @@
+use crate::error::RngError;
+use crate::genrandom::GenRandom;
+use crate::rng::SplitMix64;
+use core::sync::atomic::{AtomicPtr, AtomicU64, AtomicU8, Ordering};
+use core::{mem, ptr};
+use rand_core::SeedableRng;
+
+#[cfg(feature = "std")]
+use crate::time_seeded::time_seed;
+
+/// A source of random bytes to seed engines from.
+///
+/// Implemented for [`OsEntropy`], for [`GlobalEntropy`] and for closures, so a hardware TRNG
+/// or a test stub can be plugged in wherever entropy is needed.
+pub trait EntropySource {
+    /// Fill `dest` entirely with random bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the source fails to generate random bytes.
+    fn fill(&mut self, dest: &mut [u8]) -> Result<(), RngError>;
+}
+
+impl<F> EntropySource for F
+where
+    F: FnMut(&mut [u8]) -> Result<(), RngError>,
+{
+    #[inline]
+    fn fill(&mut self, dest: &mut [u8]) -> Result<(), RngError> {
+        self(dest)
+    }
+}
+
+/// The OS entropy source, through the `getrandom` crate.
+#[cfg(feature = "getrandom")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OsEntropy;
+
+#[cfg(feature = "getrandom")]
+impl EntropySource for OsEntropy {
+    #[inline]
+    fn fill(&mut self, dest: &mut [u8]) -> Result<(), RngError> {
+        getrandom::fill(dest)?;
+        Ok(())
+    }
+}
+
+/// A function that fills a buffer with random bytes, see [`set_entropy_hook`].
+pub type EntropyHook = fn(&mut [u8]) -> Result<(), RngError>;
+
+static HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Register the process-wide entropy source used by [`GlobalEntropy`].
+///
+/// Once registered, the hook is used instead of the OS for [`GenRandom`], [`TryFromEntropy`],
+/// the engines' `Default` impls and the thread-local RNG. This is how targets without a
+/// `getrandom` backend, such as bare-metal boards with a hardware TRNG, get entropy.
+///
+/// ```rust
+/// use voxell_rng::entropy::set_entropy_hook;
+/// use voxell_rng::rng::XorShift128;
+/// use voxell_rng::RngError;
+///
+/// fn read_trng(dest: &mut [u8]) -> Result<(), RngError> {
+///     // read the hardware register here
+///     dest.fill(0x5a);
+///     Ok(())
+/// }
+///
+/// set_entropy_hook(read_trng);
+/// let rng = XorShift128::default();
+/// ```
+#[inline]
+pub fn set_entropy_hook(hook: EntropyHook) {
+    HOOK.store(hook as *mut (), Ordering::Release);
+}
+
+/// Remove the hook registered with [`set_entropy_hook`], going back to the OS entropy source.
+#[inline]
+pub fn clear_entropy_hook() {
+    HOOK.store(ptr::null_mut(), Ordering::Release);
+}
+
+#[inline]
+fn entropy_hook() -> Option<EntropyHook> {
+    let hook = HOOK.load(Ordering::Acquire);
+    if hook.is_null() {
+        return None;
+    }
+
+    // SAFETY: the only non-null values ever stored are `EntropyHook`s cast in `set_entropy_hook`.
+    Some(unsafe { mem::transmute::<*mut (), EntropyHook>(hook) })
+}
+
+/// The process-wide entropy source: the hook registered with [`set_entropy_hook`] if there is
+/// one, and the OS otherwise.
+///
+/// Without the `getrandom` feature and without a hook, filling fails with
+/// [`RngError::NoEntropySource`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GlobalEntropy;
+
+impl EntropySource for GlobalEntropy {
+    #[inline]
+    fn fill(&mut self, dest: &mut [u8]) -> Result<(), RngError> {
+        if let Some(hook) = entropy_hook() {
+            return hook(dest);
+        }
+
+        #[cfg(feature = "getrandom")]
+        return OsEntropy.fill(dest);
+
+        #[cfg(not(feature = "getrandom"))]
+        return Err(RngError::NoEntropySource);
+    }
+}
+
+/// Create any engine from an entropy source, without panicking when it isn't available.
+///
+/// Implemented for every [`SeedableRng`]: the whole seed is filled with a single call to the
+/// source and passed to [`SeedableRng::from_seed`].
+pub trait TryFromEntropy: Sized {
+    /// Create a new engine seeded from the [`GlobalEntropy`] source.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entropy source fails to generate random bytes.
+    #[inline]
+    fn try_from_entropy() -> Result<Self, RngError> {
+        Self::try_from_source(&mut GlobalEntropy)
+    }
+
+    /// Create a new engine seeded from the given entropy source.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entropy source fails to generate random bytes.
+    fn try_from_source<S>(source: &mut S) -> Result<Self, RngError>
+    where
+        S: EntropySource + ?Sized;
+}
+
+impl<R: SeedableRng> TryFromEntropy for R {
+    #[inline]
+    fn try_from_source<S>(source: &mut S) -> Result<Self, RngError>
+    where
+        S: EntropySource + ?Sized,
+    {
+        let mut seed = <Self as SeedableRng>::Seed::default();
+        source.fill(seed.as_mut())?;
+        Ok(Self::from_seed(seed))
+    }
+}
+
+/// What the engines' `Default` impls do when entropy isn't available.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub enum EntropyFallback {
+    /// Seed from the system time (when `std` is enabled), memory addresses and a counter.
+    ///
+    /// Addresses are randomized by ASLR on most platforms, but this is much weaker than OS
+    /// entropy and may repeat across runs on targets without ASLR or a clock.
+    #[default]
+    TimeAndAddress,
+    /// Panic.
+    Panic,
+}
+
+static FALLBACK: AtomicU8 = AtomicU8::new(EntropyFallback::TimeAndAddress as u8);
+
+/// Set what the engines' `Default` impls do when entropy isn't available, for the whole
+/// process.
+#[inline]
+pub fn set_entropy_fallback(policy: EntropyFallback) {
+    FALLBACK.store(policy as u8, Ordering::Relaxed);
+}
+
+/// What the engines' `Default` impls currently do when entropy isn't available.
+#[inline]
+#[must_use]
+pub fn entropy_fallback() -> EntropyFallback {
+    if FALLBACK.load(Ordering::Relaxed) == EntropyFallback::Panic as u8 {
+        EntropyFallback::Panic
+    } else {
+        EntropyFallback::TimeAndAddress
+    }
+}
+
+/// A 64-bit seed from the [`GlobalEntropy`] source, falling back according to [`entropy_fallback`].
+#[inline]
+#[track_caller]
+pub(crate) fn default_seed() -> u64 {
+    match u64::get_random() {
+        Ok(seed) => seed,
+        Err(err) => match entropy_fallback() {
+            EntropyFallback::TimeAndAddress => fallback_seed(),
+            EntropyFallback::Panic => panic!("{err}"),
+        },
+    }
+}
+
+/// An engine with its whole seed filled from the [`GlobalEntropy`] source, falling back
+/// according to [`entropy_fallback`].
+#[inline]
+#[track_caller]
+pub(crate) fn default_rng<R: SeedableRng>() -> R {
+    rng_from_source_or_fallback(&mut GlobalEntropy)
+}
+
+#[inline]
+#[track_caller]
+fn rng_from_source_or_fallback<R, S>(source: &mut S) -> R
+where
+    R: SeedableRng,
+    S: EntropySource + ?Sized,
+{
+    match R::try_from_source(source) {
+        Ok(rng) => rng,
+        Err(err) => match entropy_fallback() {
+            EntropyFallback::TimeAndAddress => R::seed_from_u64(fallback_seed()),
+            EntropyFallback::Panic => panic!("{err}"),
+        },
+    }
+}
+
+/// Mix whatever varies between calls and runs without asking for entropy.
+#[inline]
+fn fallback_seed() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let local = 0_u8;
+    let stack = ptr::from_ref(&local).addr() as u64;
+    let data = ptr::from_ref(&COUNTER).addr() as u64;
+    let code = (fallback_seed as fn() -> u64 as *const ()).addr() as u64;
+    let counter = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    #[cfg(feature = "std")]
+    let time = time_seed().unwrap_or(0);
+    #[cfg(not(feature = "std"))]
+    let time = 0;
+
+    [stack, data, code, counter, time]
+        .into_iter()
+        .fold(0, |seed, word| SplitMix64::wrap(seed ^ word).mix())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rng_from_source_or_fallback, RngError};
+    use crate::rng::XoRoShiRo128;
+
+    #[test]
+    fn the_whole_seed_comes_from_the_source() {
+        let mut source = |dest: &mut [u8]| {
+            for (byte, i) in dest.iter_mut().zip(1..) {
+                *byte = i;
+            }
+            Ok::<_, RngError>(())
+        };
+        let rng: XoRoShiRo128 = rng_from_source_or_fallback(&mut source);
+        assert_eq!(rng.get_current_state(), [0x0807_0605_0403_0201, 0x100f_0e0d_0c0b_0a09]);
+    }
+
+    #[test]
+    fn a_failing_source_falls_back() {
+        let mut source = |_: &mut [u8]| Err(RngError::NoEntropySource);
+        let rng: XoRoShiRo128 = rng_from_source_or_fallback(&mut source);
+        assert_ne!(rng.get_current_state(), [0, 0]);
+    }
+}
@@ -0,0 +1,84 @@
+use core::error::Error;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use std::time::SystemTimeError;
+
+/// Everything that can go wrong while seeding an RNG.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RngError {
+    /// The OS entropy source failed to generate random bytes.
+    #[cfg(feature = "getrandom")]
+    Entropy(getrandom::Error),
+    /// A custom entropy source failed to generate random bytes, the message says why.
+    EntropySource(&'static str),
+    /// The `getrandom` feature is disabled and no entropy hook is registered.
+    NoEntropySource,
+    /// The system time could not be obtained.
+    #[cfg(feature = "std")]
+    Time(SystemTimeError),
+    /// The seed is not a valid state for the engine, the message says why.
+    InvalidSeed(&'static str),
+    /// The bytes are not a valid snapshot, the message says why.
+    InvalidSnapshot(&'static str),
+    /// The string is not a valid engine spec, the message says why.
+    InvalidSpec(&'static str),
+    /// A line of a draw log is not a valid draw, the message says why.
+    InvalidLog(&'static str),
+}
+
+impl fmt::Display for RngError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            #[cfg(feature = "getrandom")]
+            Self::Entropy(ref err) => write!(f, "failed to get OS entropy: {err}"),
+            Self::EntropySource(reason) => write!(f, "failed to get entropy: {reason}"),
+            Self::NoEntropySource => f.write_str("no entropy source: the `getrandom` feature is disabled and no entropy hook is registered"),
+            #[cfg(feature = "std")]
+            Self::Time(ref err) => write!(f, "failed to get the system time: {err}"),
+            Self::InvalidSeed(reason) => write!(f, "invalid seed: {reason}"),
+            Self::InvalidSnapshot(reason) => write!(f, "invalid snapshot: {reason}"),
+            Self::InvalidSpec(reason) => write!(f, "invalid engine spec: {reason}"),
+            Self::InvalidLog(reason) => write!(f, "invalid draw log: {reason}"),
+        }
+    }
+}
+
+impl Error for RngError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            // `getrandom::Error` only implements `Error` when its `std` feature is enabled
+            #[cfg(all(feature = "std", feature = "getrandom"))]
+            Self::Entropy(ref err) => Some(err),
+            #[cfg(all(not(feature = "std"), feature = "getrandom"))]
+            Self::Entropy(_) => None,
+            #[cfg(feature = "std")]
+            Self::Time(ref err) => Some(err),
+            Self::EntropySource(_)
+            | Self::NoEntropySource
+            | Self::InvalidSeed(_)
+            | Self::InvalidSnapshot(_)
+            | Self::InvalidSpec(_)
+            | Self::InvalidLog(_) => None,
+        }
+    }
+}
+
+#[cfg(feature = "getrandom")]
+impl From<getrandom::Error> for RngError {
+    #[inline]
+    fn from(err: getrandom::Error) -> Self {
+        Self::Entropy(err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<SystemTimeError> for RngError {
+    #[inline]
+    fn from(err: SystemTimeError) -> Self {
+        Self::Time(err)
+    }
+}
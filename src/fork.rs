@@ -0,0 +1,37 @@
+//! Cheap detection of `fork()`, so generators seeded in the parent don't repeat themselves in the child.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+static FORK_GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+/// A counter that increases every time the process forks.
+///
+/// Comparing two generations is a single atomic load, unlike comparing process ids which
+/// costs a syscall on every check. Forks that happen before the first call aren't counted,
+/// which is fine since nothing could have been seeded yet.
+#[inline]
+pub fn generation() -> usize {
+    #[cfg(unix)]
+    register_fork_handler();
+
+    FORK_GENERATION.load(Ordering::Relaxed)
+}
+
+#[cfg(unix)]
+#[inline]
+fn register_fork_handler() {
+    use std::sync::Once;
+
+    extern "C" fn on_fork_child() {
+        FORK_GENERATION.fetch_add(1, Ordering::Relaxed);
+    }
+
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| {
+        // SAFETY: the child handler only touches an atomic, which is async-signal-safe. If
+        // registration fails we simply never notice forks, same as on non-unix targets.
+        unsafe {
+            libc::pthread_atfork(None, None, Some(on_fork_child));
+        }
+    });
+}
@@ -1,15 +1,95 @@
-//! This module provides a simple way to generate random numbers using the `getrandom` crate.
+//! This module provides a simple way to generate random numbers using the global entropy source.
+//!
+//! That's OS entropy through the `getrandom` crate, unless a hook was registered with
+//! [`set_entropy_hook`](crate::entropy::set_entropy_hook).
+
+use crate::entropy::{EntropySource, GlobalEntropy};
+use crate::error::RngError;
+use core::array;
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8,
+    NonZeroUsize,
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::vec;
 
 /// A trait for types that can be magically seeded using runtime entropy.
+///
+/// A type describes how many bytes of entropy it needs and how to build itself from them.
+/// Compound types add up the bytes of their parts, so a `[u8; 32]` or a `(u32, u32)` is
+/// generated with a single call to the entropy source.
+///
+/// User types can implement it by building themselves from their fields:
+///
+/// ```rust
+/// use voxell_rng::genrandom::GenRandom;
+///
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// impl GenRandom for Point {
+///     const ENTROPY_BYTES: usize = 2 * i32::ENTROPY_BYTES;
+///
+///     fn from_entropy_bytes(bytes: &mut &[u8]) -> Self {
+///         Self {
+///             x: i32::from_entropy_bytes(bytes),
+///             y: i32::from_entropy_bytes(bytes),
+///         }
+///     }
+/// }
+///
+/// # #[cfg(feature = "getrandom")] {
+/// let key = <[u8; 32]>::get_random().unwrap();
+/// let point = Point::get_random().unwrap();
+/// # }
+/// ```
 pub trait GenRandom: Sized {
-    /// Create a new instance of the type using OS entropy.
+    /// The number of bytes of entropy [`GenRandom::from_entropy_bytes`] consumes.
+    const ENTROPY_BYTES: usize;
+
+    /// Build a value from the front of `bytes`, and advance `bytes` past the
+    /// [`GenRandom::ENTROPY_BYTES`] bytes that were used.
+    ///
+    /// Missing bytes are treated as zeros.
+    #[must_use]
+    fn from_entropy_bytes(bytes: &mut &[u8]) -> Self;
+
+    /// Create a new instance of the type using runtime entropy.
     ///
     /// Invoking this method every time you're generating a number is not a great idea for performance.
     ///
     /// # Errors
     ///
-    /// Returns an error if the underlying [`getrandom`](getrandom::getrandom) call fails to generate random bytes.
-    fn get_random() -> Result<Self, getrandom::Error>;
+    /// Returns an error if the [`GlobalEntropy`] source fails to generate random bytes.
+    #[inline]
+    fn get_random() -> Result<Self, RngError> {
+        let mut stack = [0; STACK_BYTES];
+        if let Some(bytes) = stack.get_mut(..Self::ENTROPY_BYTES) {
+            GlobalEntropy.fill(bytes)?;
+            return Ok(Self::from_entropy_bytes(&mut &*bytes));
+        }
+
+        let mut bytes = vec![0; Self::ENTROPY_BYTES];
+        GlobalEntropy.fill(&mut bytes)?;
+        Ok(Self::from_entropy_bytes(&mut bytes.as_slice()))
+    }
+}
+
+/// Types that need up to this many bytes of entropy are generated without allocating.
+const STACK_BYTES: usize = 256;
+
+/// Take `L` bytes from the front of `bytes`, zero-padding if there aren't enough.
+#[inline]
+fn take_bytes<const L: usize>(bytes: &mut &[u8]) -> [u8; L] {
+    let mut taken = [0; L];
+    let len = L.min(bytes.len());
+    let (front, rest) = bytes.split_at(len);
+    taken[..len].copy_from_slice(front);
+    *bytes = rest;
+    taken
 }
 
 macro_rules! impl_trait_for_primitive_types {
@@ -19,16 +99,23 @@ macro_rules! impl_trait_for_primitive_types {
                 [!set! #current_type = [!ident! $current_type]]
 
                 impl GenRandom for $current_type {
-                    #[doc = [!string! "Create a new [`" #current_type "`] using OS entropy.\n\n"]]
+                    const ENTROPY_BYTES: usize = core::mem::size_of::<$current_type>();
+
+                    #[inline]
+                    fn from_entropy_bytes(bytes: &mut &[u8]) -> Self {
+                        $current_type::from_le_bytes(take_bytes(bytes))
+                    }
+
+                    #[doc = [!string! "Create a new [`" #current_type "`] using runtime entropy.\n\n"]]
                     #[doc = "Invoking this method every time you're generating a number is not a great idea for performance.\n\n"]
                     #[doc = "# Errors\n\n"]
-                    #[doc = "Returns an error if the underlying [`getrandom`](getrandom::getrandom) call fails to generate random bytes."]
+                    #[doc = "Returns an error if the [`GlobalEntropy`] source fails to generate random bytes."]
                     #[inline]
-                    fn get_random() -> Result<$current_type, getrandom::Error> {
+                    fn get_random() -> Result<$current_type, RngError> {
                         const L: usize = core::mem::size_of::<$current_type>();
                         let mut bytes: [u8; L] = [0; L];
-                        getrandom::fill(&mut bytes)?;
-                        let value: $current_type = $current_type::from_ne_bytes(bytes);
+                        GlobalEntropy.fill(&mut bytes)?;
+                        let value: $current_type = $current_type::from_le_bytes(bytes);
                         Ok(value)
                     }
                     }
@@ -44,19 +131,26 @@ macro_rules! impl_trait_for_floats {
                 [!set! #current_type = [!ident! $current_type]]
 
                 impl GenRandom for $current_type {
-                    #[doc = [!string! "Create a new [`" #current_type "`] using OS entropy.\n\n"]]
+                    const ENTROPY_BYTES: usize = core::mem::size_of::<$bittype>();
+
+                    #[doc = "The resulting number will be between 0 and 1."]
+                    #[inline]
+                    fn from_entropy_bytes(bytes: &mut &[u8]) -> Self {
+                        let value: $bittype = $bittype::from_le_bytes(take_bytes(bytes));
+                        value as $current_type / $bittype::MAX as $current_type
+                    }
+
+                    #[doc = [!string! "Create a new [`" #current_type "`] using runtime entropy.\n\n"]]
                     #[doc = "Invoking this method every time you're generating a number is not a great idea for performance.\n\n"]
                     #[doc = "The resulting number will be between 0 and 1.\n\n"]
                     #[doc = "# Errors\n\n"]
-                    #[doc = "Returns an error if the underlying [`getrandom`](getrandom::getrandom) call fails to generate random bytes."]
+                    #[doc = "Returns an error if the [`GlobalEntropy`] source fails to generate random bytes."]
                     #[inline]
-                    fn get_random() -> Result<$current_type, getrandom::Error> {
+                    fn get_random() -> Result<$current_type, RngError> {
                         const L: usize = core::mem::size_of::< $current_type >();
                         let mut bytes: [u8; L] = [0; L];
-                        getrandom::fill(&mut bytes)?;
-                        let value: $bittype = $bittype::from_ne_bytes(bytes);
-                        let fvalue = value as $current_type / $bittype::MAX as $current_type;
-                        Ok(fvalue)
+                        GlobalEntropy.fill(&mut bytes)?;
+                        Ok(Self::from_entropy_bytes(&mut bytes.as_slice()))
                     }
                     }
             )*
@@ -66,3 +160,114 @@ macro_rules! impl_trait_for_floats {
 
 impl_trait_for_primitive_types!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
 impl_trait_for_floats!(f32: u32, f64: u64);
+
+impl GenRandom for bool {
+    const ENTROPY_BYTES: usize = 1;
+
+    #[inline]
+    fn from_entropy_bytes(bytes: &mut &[u8]) -> Self {
+        u8::from_entropy_bytes(bytes) & 1 == 1
+    }
+}
+
+impl GenRandom for char {
+    const ENTROPY_BYTES: usize = u64::ENTROPY_BYTES;
+
+    /// Uniform over the valid scalar values, surrogates are never generated.
+    #[inline]
+    fn from_entropy_bytes(bytes: &mut &[u8]) -> Self {
+        const SURROGATES: u32 = 0xe000 - 0xd800;
+        const SCALAR_VALUES: u64 = (char::MAX as u64) + 1 - SURROGATES as u64;
+
+        // 64 bits of entropy keep the modulo bias below 2^-43
+        let mut value = (u64::from_entropy_bytes(bytes) % SCALAR_VALUES) as u32;
+        if value >= 0xd800 {
+            value += SURROGATES;
+        }
+        Self::from_u32(value).unwrap_or_default()
+    }
+}
+
+impl<T: GenRandom> GenRandom for Option<T> {
+    const ENTROPY_BYTES: usize = 1 + T::ENTROPY_BYTES;
+
+    /// `Some` and `None` are equally likely. The bytes for the inner value are consumed either way.
+    #[inline]
+    fn from_entropy_bytes(bytes: &mut &[u8]) -> Self {
+        let is_some = bool::from_entropy_bytes(bytes);
+        let value = T::from_entropy_bytes(bytes);
+        is_some.then_some(value)
+    }
+}
+
+impl<T: GenRandom, const N: usize> GenRandom for [T; N] {
+    const ENTROPY_BYTES: usize = N * T::ENTROPY_BYTES;
+
+    #[inline]
+    fn from_entropy_bytes(bytes: &mut &[u8]) -> Self {
+        array::from_fn(|_| T::from_entropy_bytes(bytes))
+    }
+}
+
+macro_rules! impl_trait_for_nonzero {
+    ($($nonzero:ty: $unsigned:ty => $primitive:ty),* $(,)?) => {
+        $(
+            impl GenRandom for $nonzero {
+                const ENTROPY_BYTES: usize = u128::ENTROPY_BYTES;
+
+                /// Uniform over the nonzero values, 128 bits of entropy keep the modulo bias negligible.
+                #[inline]
+                #[allow(clippy::cast_possible_wrap)]
+                fn from_entropy_bytes(bytes: &mut &[u8]) -> Self {
+                    let value = 1 + (u128::from_entropy_bytes(bytes) % <$unsigned>::MAX as u128) as $unsigned;
+                    Self::new(value as $primitive).unwrap_or(Self::MIN)
+                }
+            }
+        )*
+    };
+}
+
+impl_trait_for_nonzero!(
+    NonZeroU8: u8 => u8,
+    NonZeroU16: u16 => u16,
+    NonZeroU32: u32 => u32,
+    NonZeroU64: u64 => u64,
+    NonZeroU128: u128 => u128,
+    NonZeroUsize: usize => usize,
+    NonZeroI8: u8 => i8,
+    NonZeroI16: u16 => i16,
+    NonZeroI32: u32 => i32,
+    NonZeroI64: u64 => i64,
+    NonZeroI128: u128 => i128,
+    NonZeroIsize: usize => isize,
+);
+
+macro_rules! impl_trait_for_tuples {
+    ($(($($name:ident),+)),* $(,)?) => {
+        $(
+            impl<$($name: GenRandom),+> GenRandom for ($($name,)+) {
+                const ENTROPY_BYTES: usize = 0 $(+ $name::ENTROPY_BYTES)+;
+
+                #[inline]
+                fn from_entropy_bytes(bytes: &mut &[u8]) -> Self {
+                    ($($name::from_entropy_bytes(bytes),)+)
+                }
+            }
+        )*
+    };
+}
+
+impl_trait_for_tuples!(
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H),
+    (A, B, C, D, E, F, G, H, I),
+    (A, B, C, D, E, F, G, H, I, J),
+    (A, B, C, D, E, F, G, H, I, J, K),
+    (A, B, C, D, E, F, G, H, I, J, K, L),
+);
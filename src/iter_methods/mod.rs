@@ -0,0 +1,3 @@
+pub use reservoir::*;
+
+mod reservoir;
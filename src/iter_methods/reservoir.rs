@@ -0,0 +1,378 @@
+use crate::math;
+use crate::prelude::RngCoreExtension;
+use core::array;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// Reservoir sampling extension methods for iterators.
+///
+/// These consume the iterator in a single pass and never collect it into memory, so they
+/// work on streams whose length isn't known up front. The order of the items in the
+/// returned reservoirs is unspecified.
+pub trait IteratorRandomExt: Iterator + Sized {
+    /// Select a single random item from the iterator.
+    ///
+    /// If the iterator reports an exact length through [`Iterator::size_hint`], the item is
+    /// picked directly with [`Iterator::nth`] instead of walking the whole iterator.
+    ///
+    /// - If the iterator is empty, `None` is returned.
+    fn choose<R>(self, rng: &mut R) -> Option<Self::Item>
+    where
+        R: RngCoreExtension + ?Sized;
+
+    /// Select `k` non-overlapping random items from the iterator using Algorithm L.
+    ///
+    /// - If the iterator has fewer than `k` items, all of them are returned.
+    fn choose_multiple<R>(self, k: usize, rng: &mut R) -> Vec<Self::Item>
+    where
+        R: RngCoreExtension + ?Sized;
+
+    /// Select `N` non-overlapping random items from the iterator using Algorithm L,
+    /// without allocating.
+    ///
+    /// - If the iterator has fewer than `N` items, `None` is returned.
+    fn choose_multiple_array<const N: usize, R>(self, rng: &mut R) -> Option<[Self::Item; N]>
+    where
+        R: RngCoreExtension + ?Sized;
+
+    /// Select a single random item from the iterator with a probability proportional
+    /// to its weight as reported by `weight`.
+    ///
+    /// Negative, NaN and infinite weights are treated as zero, and items with a weight of
+    /// zero are never selected.
+    ///
+    /// - If the iterator has no items with a nonzero weight, `None` is returned.
+    fn choose_weighted<R, F>(self, weight: F, rng: &mut R) -> Option<Self::Item>
+    where
+        R: RngCoreExtension + ?Sized,
+        F: FnMut(&Self::Item) -> f64;
+
+    /// Select `k` non-overlapping random items from the iterator using the A-ExpJ weighted
+    /// reservoir algorithm. The weight of each item is reported by `weight`.
+    ///
+    /// Negative, NaN and infinite weights are treated as zero, and items with a weight of
+    /// zero are never selected.
+    ///
+    /// - If the iterator has fewer than `k` items with a nonzero weight, all of them are returned.
+    fn choose_multiple_weighted<R, F>(self, k: usize, weight: F, rng: &mut R) -> Vec<Self::Item>
+    where
+        R: RngCoreExtension + ?Sized,
+        F: FnMut(&Self::Item) -> f64;
+
+    /// Select `N` non-overlapping random items from the iterator using the A-ExpJ weighted
+    /// reservoir algorithm, without allocating. The weight of each item is reported by `weight`.
+    ///
+    /// Negative, NaN and infinite weights are treated as zero, and items with a weight of
+    /// zero are never selected.
+    ///
+    /// - If the iterator has fewer than `N` items with a nonzero weight, `None` is returned.
+    fn choose_multiple_weighted_array<const N: usize, R, F>(self, weight: F, rng: &mut R) -> Option<[Self::Item; N]>
+    where
+        R: RngCoreExtension + ?Sized,
+        F: FnMut(&Self::Item) -> f64;
+}
+
+impl<I: Iterator> IteratorRandomExt for I {
+    #[inline]
+    fn choose<R>(mut self, rng: &mut R) -> Option<Self::Item>
+    where
+        R: RngCoreExtension + ?Sized,
+    {
+        match self.size_hint() {
+            (0, Some(0)) => None,
+            (lower, Some(upper)) if lower == upper => self.nth(rng.next_bounded_usize(lower)),
+            _ => self.choose_multiple_array::<1, R>(rng).map(|[item]| item),
+        }
+    }
+
+    #[inline]
+    fn choose_multiple<R>(mut self, k: usize, rng: &mut R) -> Vec<Self::Item>
+    where
+        R: RngCoreExtension + ?Sized,
+    {
+        let mut reservoir: Vec<Self::Item> = self.by_ref().take(k).collect();
+        if reservoir.len() == k {
+            algorithm_l(&mut self, &mut reservoir, rng);
+        }
+
+        reservoir
+    }
+
+    #[inline]
+    fn choose_multiple_array<const N: usize, R>(mut self, rng: &mut R) -> Option<[Self::Item; N]>
+    where
+        R: RngCoreExtension + ?Sized,
+    {
+        let reservoir: [Option<Self::Item>; N] = array::from_fn(|_| self.next());
+        if reservoir.iter().any(Option::is_none) {
+            return None;
+        }
+
+        let mut reservoir = reservoir.map(|item| item.unwrap());
+        algorithm_l(&mut self, &mut reservoir, rng);
+
+        Some(reservoir)
+    }
+
+    #[inline]
+    fn choose_weighted<R, F>(self, weight: F, rng: &mut R) -> Option<Self::Item>
+    where
+        R: RngCoreExtension + ?Sized,
+        F: FnMut(&Self::Item) -> f64,
+    {
+        self.choose_multiple_weighted_array::<1, R, F>(weight, rng).map(|[item]| item)
+    }
+
+    #[inline]
+    fn choose_multiple_weighted<R, F>(mut self, k: usize, mut weight: F, rng: &mut R) -> Vec<Self::Item>
+    where
+        R: RngCoreExtension + ?Sized,
+        F: FnMut(&Self::Item) -> f64,
+    {
+        let mut items = Vec::with_capacity(k);
+        let mut keys = Vec::with_capacity(k);
+        while items.len() < k {
+            let Some((item, key)) = next_weighted(&mut self, &mut weight, rng) else {
+                return items;
+            };
+            items.push(item);
+            keys.push(key);
+        }
+
+        a_exp_j(&mut self, &mut items, &mut keys, &mut weight, rng);
+
+        items
+    }
+
+    #[inline]
+    fn choose_multiple_weighted_array<const N: usize, R, F>(mut self, mut weight: F, rng: &mut R) -> Option<[Self::Item; N]>
+    where
+        R: RngCoreExtension + ?Sized,
+        F: FnMut(&Self::Item) -> f64,
+    {
+        let reservoir: [Option<(Self::Item, f64)>; N] = array::from_fn(|_| next_weighted(&mut self, &mut weight, rng));
+        if reservoir.iter().any(Option::is_none) {
+            return None;
+        }
+
+        let mut keys = [0.0; N];
+        let mut items = {
+            let mut keys_iter = keys.iter_mut();
+            reservoir.map(|entry| {
+                let (item, key) = entry.unwrap();
+                if let Some(slot) = keys_iter.next() {
+                    *slot = key;
+                }
+                item
+            })
+        };
+        a_exp_j(&mut self, &mut items, &mut keys, &mut weight, rng);
+
+        Some(items)
+    }
+}
+
+/// A uniform float in `(0, 1]`, never zero so its logarithm is always finite.
+#[inline]
+fn open_unit_f64<R>(rng: &mut R) -> f64
+where
+    R: RngCoreExtension + ?Sized,
+{
+    ((RngCoreExtension::next_u64(rng) >> 11) + 1) as f64 / (1_u64 << 53) as f64
+}
+
+#[inline]
+fn sanitize_weight(weight: f64) -> f64 {
+    if weight.is_finite() && weight > 0.0 {
+        weight
+    } else {
+        0.0
+    }
+}
+
+/// Algorithm L: the reservoir is already filled with the first `reservoir.len()` items, replace
+/// them with the rest of the iterator while skipping over geometrically distributed runs.
+#[inline]
+fn algorithm_l<I, R>(iter: &mut I, reservoir: &mut [I::Item], rng: &mut R)
+where
+    I: Iterator,
+    R: RngCoreExtension + ?Sized,
+{
+    let k = reservoir.len();
+    if k == 0 {
+        return;
+    }
+
+    let inv_k = 1.0 / k as f64;
+    let mut w = math::exp(math::ln(open_unit_f64(rng)) * inv_k);
+    loop {
+        // both logarithms are negative, and the cast saturates so a huge skip simply drains the iterator
+        #[allow(clippy::cast_sign_loss)]
+        let skip = (math::ln(open_unit_f64(rng)) / math::ln(1.0 - w)) as usize;
+        let Some(item) = iter.nth(skip) else {
+            return;
+        };
+
+        if let Some(slot) = reservoir.get_mut(rng.next_bounded_usize(k)) {
+            *slot = item;
+        }
+        w *= math::exp(math::ln(open_unit_f64(rng)) * inv_k);
+    }
+}
+
+/// Pull items until one with a nonzero weight shows up, and compute its reservoir key.
+///
+/// Keys are kept as logarithms (`ln(u) / w` instead of `u^(1/w)`) so that small weights
+/// don't underflow to zero.
+#[inline]
+fn next_weighted<I, R, F>(iter: &mut I, weight: &mut F, rng: &mut R) -> Option<(I::Item, f64)>
+where
+    I: Iterator,
+    R: RngCoreExtension + ?Sized,
+    F: FnMut(&I::Item) -> f64,
+{
+    iter.find_map(|item| {
+        let w = sanitize_weight(weight(&item));
+        (w > 0.0).then(|| (item, math::ln(open_unit_f64(rng)) / w))
+    })
+}
+
+/// A-ExpJ (Efraimidis & Spirakis): `items` and `keys` hold the first `items.len()` items with a
+/// nonzero weight, replace them with the rest of the iterator while jumping over items whose
+/// key would be too small to enter the reservoir.
+// `mul_add` lives in `std`, so it can't be used here.
+#[allow(clippy::suboptimal_flops)]
+#[inline]
+fn a_exp_j<I, R, F>(iter: &mut I, items: &mut [I::Item], keys: &mut [f64], weight: &mut F, rng: &mut R)
+where
+    I: Iterator,
+    R: RngCoreExtension + ?Sized,
+    F: FnMut(&I::Item) -> f64,
+{
+    if items.is_empty() {
+        return;
+    }
+
+    // min-heap on the keys, the root is the item that gets evicted next
+    for i in (0..items.len() / 2).rev() {
+        sift_down(items, keys, i);
+    }
+
+    let mut jump = math::ln(open_unit_f64(rng)) / keys[0];
+    for item in iter {
+        let w = sanitize_weight(weight(&item));
+        // also keeps zero weights out when a key of zero turns the jump into NaN
+        if w <= 0.0 {
+            continue;
+        }
+        jump -= w;
+        if jump > 0.0 {
+            continue;
+        }
+
+        // the new key is uniform in (threshold, 1] where threshold = min_key^w
+        let threshold = math::exp(keys[0] * w);
+        let u = threshold + (1.0 - threshold) * open_unit_f64(rng);
+        items[0] = item;
+        keys[0] = math::ln(u) / w;
+        sift_down(items, keys, 0);
+
+        jump = math::ln(open_unit_f64(rng)) / keys[0];
+    }
+}
+
+#[inline]
+fn sift_down<T>(items: &mut [T], keys: &mut [f64], mut i: usize) {
+    let len = keys.len();
+    loop {
+        let left = 2 * i + 1;
+        let right = left + 1;
+        let mut smallest = i;
+        if left < len && keys[left] < keys[smallest] {
+            smallest = left;
+        }
+        if right < len && keys[right] < keys[smallest] {
+            smallest = right;
+        }
+        if smallest == i {
+            return;
+        }
+
+        keys.swap(i, smallest);
+        items.swap(i, smallest);
+        i = smallest;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IteratorRandomExt, Vec};
+    use crate::mock::ConstRng;
+    use crate::rng::XorShift128;
+
+    /// a draw of exactly 1 from `open_unit_f64`, the edge where the keys become zero
+    const ONE: ConstRng = ConstRng(u64::MAX);
+
+    #[test]
+    fn choose_from_empty_and_single() {
+        let mut rng = XorShift128::new(1);
+        assert_eq!((0..0).choose(&mut rng), None);
+        assert_eq!((0..10).filter(|_| false).choose(&mut rng), None);
+        assert_eq!((0..1).choose(&mut rng), Some(0));
+        assert_eq!((0..10).filter(|&x| x == 7).choose(&mut rng), Some(7));
+    }
+
+    #[test]
+    fn choose_multiple_short_iterators() {
+        let mut rng = XorShift128::new(2);
+        assert_eq!((0..5).choose_multiple(0, &mut rng), []);
+        assert_eq!((0..3).choose_multiple(5, &mut rng), [0, 1, 2]);
+        assert_eq!((0..3).choose_multiple_array::<4, _>(&mut rng), None);
+        assert_eq!((0..0).choose_multiple_array::<0, _>(&mut rng), Some([]));
+
+        let mut all = (0..4).choose_multiple_array::<4, _>(&mut rng).unwrap();
+        all.sort_unstable();
+        assert_eq!(all, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn choose_multiple_picks_distinct_items() {
+        let mut rng = XorShift128::new(3);
+        for _ in 0..100 {
+            let mut picked = (0..50).choose_multiple(10, &mut rng);
+            picked.sort_unstable();
+            picked.dedup();
+            assert_eq!(picked.len(), 10);
+            assert!(picked.iter().all(|&x| x < 50));
+        }
+    }
+
+    #[test]
+    fn zero_weights_are_never_chosen() {
+        let weight = |&x: &u32| if x % 3 == 0 { 1.0 } else { 0.0 };
+        let mut rng = XorShift128::new(4);
+        for _ in 0..100 {
+            assert_eq!((0..30).choose_weighted(weight, &mut rng).map(|x| x % 3), Some(0));
+            assert!((0..30).choose_multiple_weighted(4, weight, &mut rng).iter().all(|x| x % 3 == 0));
+        }
+
+        let mut one = ONE;
+        assert_eq!((0..30).choose_weighted(weight, &mut one).map(|x| x % 3), Some(0));
+        let picked = (0..30).choose_multiple_weighted_array::<2, _, _>(weight, &mut one).unwrap();
+        assert!(picked.iter().all(|x| x % 3 == 0));
+    }
+
+    #[test]
+    fn fewer_nonzero_weights_than_k() {
+        let weight = |&x: &u32| if x < 3 { 2.0 } else { f64::NAN };
+        let mut rng = XorShift128::new(5);
+        let mut picked: Vec<u32> = (0..10).choose_multiple_weighted(5, weight, &mut rng);
+        picked.sort_unstable();
+        assert_eq!(picked, [0, 1, 2]);
+        assert_eq!((0..10).choose_multiple_weighted_array::<4, _, _>(weight, &mut rng), None);
+        assert_eq!((0..10).choose_weighted(|_| 0.0, &mut rng), None);
+    }
+}
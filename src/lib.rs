@@ -5,14 +5,19 @@
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 
+#[cfg(feature = "getrandom")]
 extern crate getrandom;
 extern crate preinterpret;
 extern crate rand_core;
 
+// the derive macros name this crate as `::voxell_rng`, so the unit tests of the derive need the name too
+#[cfg(all(test, feature = "derive"))]
+extern crate self as voxell_rng;
+
 /// RNG branching mechanics for parallel random numbers
 pub mod branch_rng;
 
-/// Seed RNGs in runtime using OS entropy
+/// Seed RNGs in runtime using the global entropy source
 pub mod genrandom;
 
 /// RNG engines
@@ -21,6 +26,63 @@ pub mod rng;
 /// Methods on slices that require randomness
 pub mod slice_methods;
 
+/// Methods on iterators that require randomness
+pub mod iter_methods;
+
+/// Random permutations of `0..n` that don't store the permutation
+pub mod permutation;
+
+/// Generating values of a type, and values in a range, from any RNG
+pub mod sample;
+
+/// Reproducible RNGs derived from a root seed and a key path
+pub mod seed_tree;
+
+/// numpy-compatible seed sequences for expanding arbitrary entropy into seeds
+pub mod seed_sequence;
+
+/// Seed RNGs from human-readable strings
+pub mod str_seed;
+
+/// Versioned binary snapshots of engine state for save files
+pub mod snapshot;
+
+/// Reading, overwriting and peeking at the state of every engine
+pub mod stateful;
+
+/// Recording and replaying the draws of an RNG for deterministic debugging
+pub mod recording;
+
+/// Deterministic RNGs that return chosen values, for unit tests and fuzzing
+pub mod mock;
+
+/// Engines that reseed themselves from an entropy source every so often
+pub mod reseeding;
+
+/// Randomized tests that report the seed of a failing case
+#[cfg(feature = "std")]
+pub mod testing;
+
+/// Lazily seeded thread-local RNG
+#[cfg(feature = "std")]
+pub mod thread_rng;
+
+#[cfg(feature = "std")]
+pub use thread_rng::{choose, random, random_range, shuffle, thread_rng};
+
+#[cfg(feature = "std")]
+mod fork;
+mod lineage;
+mod math;
+
+/// Errors returned by this crate
+pub mod error;
+
+pub use error::RngError;
+
+/// Pluggable entropy sources and what to do when they aren't available
+pub mod entropy;
+
 /// Seed RNGs using the system time
 #[cfg(feature = "std")]
 pub mod time_seeded;
@@ -28,6 +90,12 @@ pub mod time_seeded;
 /// prelude
 pub mod prelude;
 
+/// Paths used by the code generated by the derive macros, not public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use rand_core::RngCore;
+}
+
 /// extension methods for [`RngCore`]
 ///
 /// covers all the basic integer types
@@ -0,0 +1,216 @@
+//! Clone lineage diagnostics, enabled with the `clone-lineage` feature in builds with `debug_assertions`,
+//! unless the `strict` feature removes `Clone` altogether.
+//!
+//! Every clone of an engine records its state and where it was made. When two different
+//! engines draw from the same recorded state, they are about to produce the same numbers, and
+//! a warning naming the clones is printed to stderr. Without the feature, or in release
+//! builds, the hooks compile to nothing.
+//!
+//! Engines are told apart by their address, so the short-lived copies the crate makes
+//! internally, such as the one [`StatefulRng::peek_n`](crate::stateful::StatefulRng::peek_n)
+//! looks ahead on, draw inside [`untracked`] to not be mistaken for a clone.
+
+#[cfg(all(feature = "clone-lineage", debug_assertions, not(feature = "strict")))]
+mod tracking {
+    use crate::stateful::StatefulRng;
+    use core::any;
+    use core::cell::Cell;
+    use core::hash::{Hash, Hasher};
+    use core::panic::Location;
+    use core::ptr;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use std::hash::DefaultHasher;
+    use std::sync::{Mutex, PoisonError};
+
+    /// The most clone states remembered at once, the oldest are forgotten first.
+    const MAX_TRACKED: usize = 1024;
+
+    struct Tracked {
+        engine: &'static str,
+        fingerprint: u64,
+        /// every clone made of this state, a clone of a clone shares its parent's state
+        made_at: Vec<&'static Location<'static>>,
+        /// the address of the first engine that drew from this state
+        first_drawn_by: Option<usize>,
+    }
+
+    static CLONES: Mutex<Vec<Tracked>> = Mutex::new(Vec::new());
+    static TRACKED: AtomicUsize = AtomicUsize::new(0);
+
+    std::thread_local! {
+        /// set while the current thread runs [`untracked`]
+        static UNTRACKED: Cell<bool> = const { Cell::new(false) };
+    }
+
+    /// Restores the previous [`UNTRACKED`] flag, even if the closure panics.
+    struct Untracked {
+        outer: bool,
+    }
+
+    impl Drop for Untracked {
+        #[inline]
+        fn drop(&mut self) {
+            UNTRACKED.set(self.outer);
+        }
+    }
+
+    #[inline]
+    fn fingerprint<R: StatefulRng>(rng: &R) -> u64
+    where
+        R::State: Hash,
+    {
+        let mut hasher = DefaultHasher::new();
+        rng.state().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn cloned<R: StatefulRng>(rng: &R)
+    where
+        R::State: Hash,
+    {
+        let engine = any::type_name::<R>();
+        let fingerprint = fingerprint(rng);
+        let made_at = Location::caller();
+
+        let mut clones = CLONES.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(clone) = clones.iter_mut().find(|clone| clone.engine == engine && clone.fingerprint == fingerprint) {
+            clone.made_at.push(made_at);
+            return;
+        }
+
+        if clones.len() == MAX_TRACKED {
+            clones.remove(0);
+        }
+        clones.push(Tracked {
+            engine,
+            fingerprint,
+            made_at: vec![made_at],
+            first_drawn_by: None,
+        });
+        TRACKED.store(clones.len(), Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn stepped<R: StatefulRng>(rng: &R)
+    where
+        R::State: Hash,
+    {
+        if TRACKED.load(Ordering::Relaxed) == 0 || UNTRACKED.get() {
+            return;
+        }
+
+        let engine = any::type_name::<R>();
+        let fingerprint = fingerprint(rng);
+        let address = ptr::from_ref(rng) as usize;
+
+        let mut clones = CLONES.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(index) = clones.iter().position(|clone| clone.engine == engine && clone.fingerprint == fingerprint) else {
+            return;
+        };
+        let Some(clone) = clones.get_mut(index) else {
+            return;
+        };
+        match clone.first_drawn_by {
+            None => clone.first_drawn_by = Some(address),
+            Some(first) if first == address => {}
+            Some(_) => {
+                let clone = clones.remove(index);
+                TRACKED.store(clones.len(), Ordering::Relaxed);
+                drop(clones);
+
+                eprintln!("warning: two copies of a {engine} are drawing the same numbers");
+                for made_at in clone.made_at {
+                    eprintln!("  cloned at {made_at}");
+                }
+                eprintln!("  use `ForkRng::fork` to split off an independent generator instead of `Clone`");
+            }
+        }
+    }
+
+    #[inline]
+    pub fn untracked<T>(f: impl FnOnce() -> T) -> T {
+        let _restore = Untracked {
+            outer: UNTRACKED.replace(true),
+        };
+        f()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{fingerprint, CLONES};
+        use crate::rng::XorShift128;
+        use crate::stateful::StatefulRng;
+        use core::ptr;
+        use rand_core::RngCore;
+        use std::sync::PoisonError;
+
+        #[test]
+        fn peeking_is_not_a_draw() {
+            let mut rng = XorShift128::new(0x9e3c_a41d);
+            let clone = rng.clone();
+            let _: [u64; 2] = rng.peek_n();
+            rng.next_u64();
+
+            let clones = CLONES.lock().unwrap_or_else(PoisonError::into_inner);
+            let tracked = clones.iter().find(|tracked| tracked.fingerprint == fingerprint(&clone));
+            assert_eq!(
+                tracked.and_then(|tracked| tracked.first_drawn_by),
+                Some(ptr::from_ref(&rng) as usize),
+                "only `rng` should have drawn from the cloned state"
+            );
+        }
+    }
+}
+
+#[cfg(all(feature = "clone-lineage", debug_assertions, not(feature = "strict")))]
+pub use tracking::{cloned, stepped, untracked};
+
+/// Record a clone of an engine.
+#[cfg(not(any(all(feature = "clone-lineage", debug_assertions), feature = "strict")))]
+#[inline]
+pub const fn cloned<R>(_rng: &R) {}
+
+/// Check an engine that is about to draw against the recorded clones.
+#[cfg(not(all(feature = "clone-lineage", debug_assertions, not(feature = "strict"))))]
+#[inline]
+pub const fn stepped<R>(_rng: &R) {}
+
+/// Run `f` without checking the draws it makes against the recorded clones.
+#[cfg(not(all(feature = "clone-lineage", debug_assertions, not(feature = "strict"))))]
+#[inline]
+pub fn untracked<T>(f: impl FnOnce() -> T) -> T {
+    f()
+}
+
+/// `Clone` for an engine, tracked by [`cloned`] and left out with the `strict` feature.
+///
+/// Also adds the untracked copy behind [`StatefulRng::duplicate`](crate::stateful::StatefulRng::duplicate),
+/// which is kept with the `strict` feature.
+macro_rules! impl_tracked_clone {
+    ($($engine:ident { $($field:ident),* };)*) => {
+        $(
+            impl $engine {
+                /// a copy of the engine, neither tracked nor removed by the `strict` feature
+                #[inline]
+                pub(crate) fn untracked_copy(&self) -> Self {
+                    Self { $($field: Clone::clone(&self.$field)),* }
+                }
+            }
+
+            #[cfg(not(feature = "strict"))]
+            impl Clone for $engine {
+                #[inline]
+                #[track_caller]
+                fn clone(&self) -> Self {
+                    let clone = self.untracked_copy();
+                    $crate::lineage::cloned(&clone);
+                    clone
+                }
+            }
+        )*
+    };
+}
+
+pub(crate) use impl_tracked_clone;
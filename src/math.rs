@@ -0,0 +1,98 @@
+//! Floating point functions that `core` doesn't provide in `no_std` builds.
+//!
+//! These are accurate to a couple ULPs, which is plenty for turning uniform
+//! draws into skip lengths and reservoir keys.
+
+// `mul_add` lives in `std`, so it can't be used here. The bit twiddling casts
+// below only ever see masked exponents that fit comfortably in either type.
+#![allow(clippy::suboptimal_flops, clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+
+use core::f64::consts::{LN_2, SQRT_2};
+
+const LN_2_HI: f64 = f64::from_bits(0x3fe6_2e42_fee0_0000);
+const LN_2_LO: f64 = f64::from_bits(0x3dea_39ef_3579_3c76);
+
+/// Natural logarithm.
+///
+/// Returns `-inf` for `0.0` and `NaN` for negative inputs.
+#[inline]
+pub fn ln(x: f64) -> f64 {
+    if x.is_nan() || x < 0.0 {
+        return f64::NAN;
+    }
+    if x == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if x.is_infinite() {
+        return f64::INFINITY;
+    }
+
+    // split x into mantissa * 2^exponent, handling subnormals by scaling them up first
+    let (x, bias) = if x < f64::MIN_POSITIVE { (x * (1_u64 << 54) as f64, 54) } else { (x, 0) };
+    let bits = x.to_bits();
+    let mut exponent = ((bits >> 52) & 0x7ff) as i64 - 1023 - bias;
+    let mut mantissa = f64::from_bits((bits & 0x000f_ffff_ffff_ffff) | 0x3ff0_0000_0000_0000);
+
+    // center the mantissa around 1 so the series below converges quickly
+    if mantissa > SQRT_2 {
+        mantissa /= 2.0;
+        exponent += 1;
+    }
+
+    // ln(m) = 2 * atanh(s) with s = (m - 1) / (m + 1), |s| < 0.172
+    let s = (mantissa - 1.0) / (mantissa + 1.0);
+    let s2 = s * s;
+    let mut term = s;
+    let mut sum = 0.0;
+    for k in (1..40_u32).step_by(2) {
+        sum += term / f64::from(k);
+        term *= s2;
+    }
+
+    2.0 * sum + exponent as f64 * LN_2
+}
+
+/// Exponential function.
+#[inline]
+pub fn exp(x: f64) -> f64 {
+    if x.is_nan() {
+        return f64::NAN;
+    }
+    if x > 709.782_712_893_384 {
+        return f64::INFINITY;
+    }
+    if x < -745.133_219_101_941_1 {
+        return 0.0;
+    }
+
+    // x = k * ln(2) + r with |r| <= ln(2) / 2, ln(2) is split in two parts so that
+    // `k * LN_2_HI` is exact and large `k` don't lose precision in the subtraction
+    let k = round_to_i64(x / LN_2);
+    let r = (x - k as f64 * LN_2_HI) - k as f64 * LN_2_LO;
+
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for n in 1..20_u32 {
+        term *= r / f64::from(n);
+        sum += term;
+    }
+
+    // scale by 2^k in two steps so neither factor overflows or goes subnormal on its own
+    let half = k / 2;
+    sum * pow2(half) * pow2(k - half)
+}
+
+#[inline]
+fn round_to_i64(x: f64) -> i64 {
+    if x >= 0.0 {
+        (x + 0.5) as i64
+    } else {
+        (x - 0.5) as i64
+    }
+}
+
+/// `2^k` for `-1022 <= k <= 1023`.
+#[inline]
+const fn pow2(k: i64) -> f64 {
+    f64::from_bits(((k + 1023) as u64) << 52)
+}
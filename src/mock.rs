@@ -0,0 +1,320 @@
+//! Every RNG here except [`BytesRng`] is a source of 64-bit words, and consumes them like this:
+//!
+//! - [`RngCore::next_u64`] takes one word.
+//! - [`RngCore::next_u32`] takes one word and returns its low 32 bits.
+//! - [`RngCore::fill_bytes`] takes one word for every started 8-byte chunk and writes it in
+//!   little-endian byte order, so a final partial chunk gets the low bytes of its word.
+//!
+//! The [`RngCoreExtension`](crate::rng_core_extension::RngCoreExtension) methods all go through
+//! [`RngCore::fill_bytes`], so:
+//!
+//! - `next_u8` to `next_u64`, `next_i8` to `next_i64`, `next_usize` and `next_isize` take one
+//!   word and return its low bits. `usize` and `isize` take a whole word on every target.
+//! - `next_u128` and `next_i128` take two words, the first one is the low half.
+//! - `next_bool` takes one word and returns `true` if it is even.
+//! - `next_bounded_u64(bound)` and `next_bounded_usize(bound)` take one word through
+//!   [`RngCore::next_u64`] and return the high half of `word * bound`. If the low half is
+//!   below `2^64 % bound` the word is rejected and another one is taken, [`bounded_word`]
+//!   gives the word that returns a chosen value on the first try.
+//!
+//! [`Shuffle::shuffle_with`](crate::slice_methods::Shuffle::shuffle_with) walks `i` from the
+//! last index down to 1 and swaps item `i` with item `next_usize() % (i + 1)`, so the words
+//! `[2, 0]` shuffle `[a, b, c]` into `[b, a, c]`:
+//!
+//! ```rust
+//! use voxell_rng::mock::SequenceRng;
+//! use voxell_rng::prelude::*;
+//!
+//! let mut rng = SequenceRng::new(&[2, 0]);
+//! let mut deck = ['a', 'b', 'c'];
+//! deck.shuffle_with(&mut rng);
+//! assert_eq!(deck, ['b', 'a', 'c']);
+//! assert_eq!(rng.consumed(), 2);
+//! ```
+
+use crate::rng::polyfill::polyfill_fill_bytes_u64;
+use crate::rng::SplitMix64;
+use rand_core::RngCore;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// The word that makes a bounded draw return `value` in `0..bound` without being rejected.
+///
+/// ```rust
+/// use voxell_rng::mock::{bounded_word, ConstRng};
+/// use voxell_rng::prelude::*;
+///
+/// let mut rng = ConstRng(bounded_word(4, 6));
+/// assert_eq!(rng.next_bounded_u64(6), 4);
+/// assert_eq!(rng.next_bounded_usize(6), 4);
+/// ```
+///
+/// # Panics
+///
+/// This will panic if `value` is not below `bound`.
+#[inline]
+#[must_use]
+#[track_caller]
+pub const fn bounded_word(value: u64, bound: u64) -> u64 {
+    assert!(value < bound, "the value of a bounded draw has to be below its bound");
+    // the low half of `word * bound` has to be at least `threshold` to be accepted
+    let threshold = bound.wrapping_neg() % bound;
+    let target = ((value as u128) << 64) | threshold as u128;
+    target.div_ceil(bound as u128) as u64
+}
+
+/// returns the given words in order, for unit tests
+///
+/// See the [module docs](self) for how each method consumes the words.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct SequenceRng {
+    words: Vec<u64>,
+    next: usize,
+    cycle: bool,
+}
+
+impl SequenceRng {
+    /// Return the words in `words` once, and panic when more are drawn.
+    #[inline]
+    #[must_use]
+    pub fn new(words: &[u64]) -> Self {
+        Self {
+            words: words.to_vec(),
+            next: 0,
+            cycle: false,
+        }
+    }
+
+    /// Return the words in `words` over and over again.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if `words` is empty.
+    #[inline]
+    #[must_use]
+    #[track_caller]
+    pub fn cycling(words: &[u64]) -> Self {
+        assert!(!words.is_empty(), "SequenceRng cannot cycle through no words");
+        Self {
+            words: words.to_vec(),
+            next: 0,
+            cycle: true,
+        }
+    }
+
+    /// The number of words drawn so far, counting every pass of a cycling sequence.
+    #[inline]
+    #[must_use]
+    pub const fn consumed(&self) -> usize {
+        self.next
+    }
+
+    /// Whether every word has been drawn at least once.
+    #[inline]
+    #[must_use]
+    pub const fn is_exhausted(&self) -> bool {
+        self.next >= self.words.len()
+    }
+
+    #[inline]
+    fn word(&mut self) -> u64 {
+        let index = if self.cycle { self.next % self.words.len() } else { self.next };
+        let Some(&word) = self.words.get(index) else {
+            panic!("SequenceRng ran out after {} words", self.words.len());
+        };
+        self.next += 1;
+        word
+    }
+}
+
+impl RngCore for SequenceRng {
+    /// Fill `dest` with the little-endian bytes of the next words.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if the sequence doesn't cycle and runs out.
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        polyfill_fill_bytes_u64(Self::word)(self, dest);
+    }
+
+    /// Return the low half of the next word.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if the sequence doesn't cycle and runs out.
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.word() as u32
+    }
+
+    /// Return the next word.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if the sequence doesn't cycle and runs out.
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.word()
+    }
+}
+
+/// returns the same word forever, for unit tests
+///
+/// See the [module docs](self) for how each method consumes the word. A word that a bounded
+/// draw rejects is rejected every time, so the draw never returns. 0 is rejected by every
+/// bound that isn't a power of two, use [`bounded_word`] to pick a word for a bounded draw.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct ConstRng(pub u64);
+
+impl RngCore for ConstRng {
+    /// Fill `dest` with the little-endian bytes of the word, repeated.
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        polyfill_fill_bytes_u64(Self::next_u64)(self, dest);
+    }
+
+    /// Return the low half of the word.
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.0 as u32
+    }
+
+    /// Return the word.
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.0
+    }
+}
+
+/// returns `start`, `start + step`, `start + 2 * step` and so on, wrapping around, for unit tests
+///
+/// See the [module docs](self) for how each method consumes the words.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct StepRng {
+    next: u64,
+    step: u64,
+}
+
+impl StepRng {
+    /// Start counting at `start`, adding `step` after every word.
+    #[inline]
+    #[must_use]
+    pub const fn new(start: u64, step: u64) -> Self {
+        Self { next: start, step }
+    }
+
+    #[inline]
+    const fn word(&mut self) -> u64 {
+        let word = self.next;
+        self.next = word.wrapping_add(self.step);
+        word
+    }
+}
+
+impl RngCore for StepRng {
+    /// Fill `dest` with the little-endian bytes of the next words.
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        polyfill_fill_bytes_u64(Self::word)(self, dest);
+    }
+
+    /// Return the low half of the next word.
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.word() as u32
+    }
+
+    /// Return the next word.
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.word()
+    }
+}
+
+/// returns the bytes of an input in order, for fuzzing
+///
+/// Unlike the other RNGs in this module it is a source of bytes, not words:
+///
+/// - [`RngCore::fill_bytes`] copies the next bytes of the input.
+/// - [`RngCore::next_u32`] and [`RngCore::next_u64`] read the next 4 or 8 bytes as a
+///   little-endian integer.
+/// - The [`RngCoreExtension`](crate::rng_core_extension::RngCoreExtension) methods read as many
+///   bytes as their type is wide, `next_usize` and `next_isize` read 8 bytes on every target and
+///   `next_bool` reads one byte and returns `true` if it is even.
+/// - The bounded draws read 8 bytes through [`RngCore::next_u64`], and another 8 bytes whenever
+///   the draw is rejected.
+///
+/// Once the input runs out, the bytes continue from `SplitMix64::wrap(0)`, so every input
+/// drives the code under test to completion the same way every time.
+///
+/// ```rust
+/// use voxell_rng::mock::BytesRng;
+/// use voxell_rng::prelude::*;
+///
+/// let mut rng = BytesRng::new(&[1, 2, 3]);
+/// assert_eq!(rng.next_u16(), 0x0201);
+/// assert_eq!(rng.next_u8(), 3);
+/// assert!(rng.is_exhausted());
+/// ```
+#[cfg_attr(not(feature = "strict"), derive(Clone))]
+#[derive(Debug, Hash, PartialEq, Eq)]
+pub struct BytesRng<'a> {
+    input: &'a [u8],
+    fallback: SplitMix64,
+}
+
+impl<'a> BytesRng<'a> {
+    /// Draw from the bytes of `input`.
+    #[inline]
+    #[must_use]
+    pub const fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            fallback: SplitMix64::wrap(0),
+        }
+    }
+
+    /// The part of the input that hasn't been drawn yet.
+    #[inline]
+    #[must_use]
+    pub const fn remaining(&self) -> &'a [u8] {
+        self.input
+    }
+
+    /// Whether the whole input has been drawn, so the bytes come from the fallback.
+    #[inline]
+    #[must_use]
+    pub const fn is_exhausted(&self) -> bool {
+        self.input.is_empty()
+    }
+}
+
+impl RngCore for BytesRng<'_> {
+    /// Fill `dest` with the next bytes of the input.
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let (head, tail) = dest.split_at_mut(dest.len().min(self.input.len()));
+        let (taken, rest) = self.input.split_at(head.len());
+        head.copy_from_slice(taken);
+        self.input = rest;
+        self.fallback.fill_bytes(tail);
+    }
+
+    /// Return the next 4 bytes of the input as a little-endian `u32`.
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    /// Return the next 8 bytes of the input as a little-endian `u64`.
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+}
@@ -0,0 +1,288 @@
+use crate::prelude::RngCoreExtension;
+use crate::rng::SplitMix64;
+use core::iter::FusedIterator;
+
+const ROUNDS: usize = 6;
+
+/// A random permutation of `0..len`, computed on demand with a Feistel network.
+///
+/// The permutation takes constant memory no matter how big `len` is, and both
+/// directions are available: [`RandomPermutation::index`] maps a position to the
+/// value at that position and [`RandomPermutation::inverse`] maps it back.
+///
+/// The network works on the smallest even number of bits that fits `len`, and values
+/// that land outside of `0..len` are fed through it again until they land inside
+/// (cycle-walking). Since the domain is less than `4 * len`, that takes fewer than
+/// 4 rounds through the network on average.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct RandomPermutation {
+    len: u64,
+    half_bits: u32,
+    mask: u64,
+    keys: [u64; ROUNDS],
+}
+
+impl RandomPermutation {
+    /// Create a new permutation of `0..len` with round keys drawn from the given rng.
+    #[inline]
+    #[must_use]
+    pub fn new<R>(len: u64, rng: &mut R) -> Self
+    where
+        R: RngCoreExtension + ?Sized,
+    {
+        let mut keys = [0; ROUNDS];
+        for key in &mut keys {
+            *key = RngCoreExtension::next_u64(rng);
+        }
+
+        Self::with_keys(len, keys)
+    }
+
+    /// Create a new permutation of `0..len` with round keys derived from `key` using a `SplitMix64` RNG.
+    ///
+    /// The same `len` and `key` always produce the same permutation.
+    #[inline]
+    #[must_use]
+    pub const fn from_key(len: u64, key: u64) -> Self {
+        let mut smx = SplitMix64::wrap(key);
+        let mut keys = [0; ROUNDS];
+        let mut i = 0;
+        while i < ROUNDS {
+            keys[i] = smx.mix();
+            i += 1;
+        }
+
+        Self::with_keys(len, keys)
+    }
+
+    #[inline]
+    const fn with_keys(len: u64, keys: [u64; ROUNDS]) -> Self {
+        // number of bits needed to represent `len - 1`, rounded up to an even number
+        let bits = u64::BITS - len.saturating_sub(1).leading_zeros();
+        let half_bits = bits.div_ceil(2);
+        let half_bits = if half_bits == 0 { 1 } else { half_bits };
+
+        Self {
+            len,
+            half_bits,
+            mask: (1 << half_bits) - 1,
+            keys,
+        }
+    }
+
+    /// The number of items being permuted.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if the permutation has no items.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The value at position `i` of the permutation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is not in `0..len`.
+    #[inline]
+    #[must_use]
+    #[track_caller]
+    pub fn index(&self, i: u64) -> u64 {
+        assert!(i < self.len, "index {i} is out of bounds for a permutation of length {}", self.len);
+
+        let mut x = self.encrypt(i);
+        while x >= self.len {
+            x = self.encrypt(x);
+        }
+        x
+    }
+
+    /// The position of value `j` in the permutation, such that `index(inverse(j)) == j`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `j` is not in `0..len`.
+    #[inline]
+    #[must_use]
+    #[track_caller]
+    pub fn inverse(&self, j: u64) -> u64 {
+        assert!(j < self.len, "value {j} is out of bounds for a permutation of length {}", self.len);
+
+        let mut x = self.decrypt(j);
+        while x >= self.len {
+            x = self.decrypt(x);
+        }
+        x
+    }
+
+    /// Iterate over the values of the permutation in permuted order.
+    #[inline]
+    #[must_use]
+    pub const fn iter(&self) -> PermutationIter<'_> {
+        PermutationIter {
+            permutation: self,
+            front: 0,
+            back: self.len,
+        }
+    }
+
+    #[inline]
+    const fn encrypt(&self, x: u64) -> u64 {
+        let mut left = x >> self.half_bits;
+        let mut right = x & self.mask;
+
+        let mut i = 0;
+        while i < ROUNDS {
+            let next = left ^ (round_function(right, self.keys[i]) & self.mask);
+            left = right;
+            right = next;
+            i += 1;
+        }
+
+        (left << self.half_bits) | right
+    }
+
+    #[inline]
+    const fn decrypt(&self, x: u64) -> u64 {
+        let mut left = x >> self.half_bits;
+        let mut right = x & self.mask;
+
+        let mut i = ROUNDS;
+        while i > 0 {
+            i -= 1;
+            let previous = right ^ (round_function(left, self.keys[i]) & self.mask);
+            right = left;
+            left = previous;
+        }
+
+        (left << self.half_bits) | right
+    }
+}
+
+#[inline]
+const fn round_function(half: u64, key: u64) -> u64 {
+    let mut x = half ^ key;
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+impl<'p> IntoIterator for &'p RandomPermutation {
+    type Item = u64;
+    type IntoIter = PermutationIter<'p>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the values of a [`RandomPermutation`] in permuted order.
+#[derive(Clone, Debug)]
+pub struct PermutationIter<'p> {
+    permutation: &'p RandomPermutation,
+    front: u64,
+    back: u64,
+}
+
+impl Iterator for PermutationIter<'_> {
+    type Item = u64;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+
+        let value = self.permutation.index(self.front);
+        self.front += 1;
+        Some(value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = usize::try_from(self.back - self.front).ok();
+        (remaining.unwrap_or(usize::MAX), remaining)
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.front = self.front.saturating_add(n as u64).min(self.back);
+        self.next()
+    }
+}
+
+impl DoubleEndedIterator for PermutationIter<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+
+        self.back -= 1;
+        Some(self.permutation.index(self.back))
+    }
+}
+
+impl FusedIterator for PermutationIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::RandomPermutation;
+    use crate::rng::XorShift128;
+
+    #[cfg(not(feature = "std"))]
+    use alloc::{vec, vec::Vec};
+
+    #[test]
+    fn bijective_over_small_domains() {
+        for len in 0..=130 {
+            for key in 0..4 {
+                let permutation = RandomPermutation::from_key(len, key);
+                let mut seen = vec![false; len as usize];
+                for i in 0..len {
+                    let value = permutation.index(i);
+                    assert!(value < len, "{value} is out of 0..{len}");
+                    assert!(!seen[value as usize], "{value} appears twice in a permutation of {len}");
+                    seen[value as usize] = true;
+                    assert_eq!(permutation.inverse(value), i);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn single_item() {
+        let permutation = RandomPermutation::new(1, &mut XorShift128::new(1));
+        assert_eq!(permutation.index(0), 0);
+        assert_eq!(permutation.inverse(0), 0);
+        assert_eq!(permutation.iter().collect::<Vec<_>>(), [0]);
+    }
+
+    #[test]
+    fn iterates_from_both_ends() {
+        let permutation = RandomPermutation::from_key(37, 9);
+        let forward: Vec<u64> = permutation.iter().collect();
+        let mut backward: Vec<u64> = permutation.iter().rev().collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+        assert_eq!(permutation.iter().nth(36), forward.last().copied());
+        assert_eq!(permutation.iter().nth(37), None);
+        assert_eq!(RandomPermutation::from_key(0, 9).iter().next(), None);
+    }
+
+    #[test]
+    fn round_trips_in_huge_domains() {
+        for len in [u64::MAX, 1 << 63, (1 << 32) + 1] {
+            let permutation = RandomPermutation::from_key(len, 3);
+            for i in [0, 1, len / 2, len - 1] {
+                assert_eq!(permutation.inverse(permutation.index(i)), i);
+            }
+        }
+    }
+}
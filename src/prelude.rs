@@ -1,5 +1,11 @@
-pub use crate::branch_rng::BranchRng;
+pub use crate::branch_rng::{BranchRng, ForkRng};
+pub use crate::entropy::TryFromEntropy;
 // we don't prelude `RngCore` so our `next_u32` and `next_u64` methods
 // don't clash with the respective `RngCore` methods.
 pub use crate::rng_core_extension::RngCoreExtension;
-pub use crate::slice_methods::Shuffle;
+pub use crate::iter_methods::IteratorRandomExt;
+pub use crate::slice_methods::{Shuffle, ShuffleExt};
+pub use crate::stateful::StatefulRng;
+pub use crate::str_seed::FromStrSeed;
+#[cfg(feature = "std")]
+pub use crate::time_seeded::TimeSeeded;
@@ -0,0 +1,500 @@
+use crate::rng::SplitMix64;
+use crate::RngError;
+use core::fmt;
+use core::str::{self, FromStr};
+use rand_core::RngCore;
+
+#[cfg(not(feature = "std"))]
+use alloc::{borrow::Cow, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::{borrow::Cow, io};
+
+/// What a single call to an RNG returned.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum DrawValue {
+    /// A call to [`RngCore::next_u32`].
+    U32(u32),
+    /// A call to [`RngCore::next_u64`].
+    U64(u64),
+    /// A call to [`RngCore::fill_bytes`].
+    Bytes(Vec<u8>),
+}
+
+impl DrawValue {
+    /// The kind of call that returned this value.
+    #[inline]
+    #[must_use]
+    pub const fn kind(&self) -> DrawKind {
+        match *self {
+            Self::U32(_) => DrawKind::U32,
+            Self::U64(_) => DrawKind::U64,
+            Self::Bytes(ref bytes) => DrawKind::Bytes(bytes.len()),
+        }
+    }
+
+    #[inline]
+    fn bytes(&self) -> Option<&[u8]> {
+        match *self {
+            Self::Bytes(ref bytes) => Some(bytes),
+            Self::U32(_) | Self::U64(_) => None,
+        }
+    }
+}
+
+/// A kind of call to an RNG, without its result.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum DrawKind {
+    /// A call to [`RngCore::next_u32`].
+    U32,
+    /// A call to [`RngCore::next_u64`].
+    U64,
+    /// A call to [`RngCore::fill_bytes`] with a buffer of this many bytes.
+    Bytes(usize),
+}
+
+impl fmt::Display for DrawKind {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::U32 => f.write_str("next_u32"),
+            Self::U64 => f.write_str("next_u64"),
+            Self::Bytes(len) => write!(f, "fill_bytes of {len} bytes"),
+        }
+    }
+}
+
+/// A call to an RNG and what it returned, as logged by [`Recording`].
+///
+/// A draw displays as one line of a text log, such as `u64 0x2a @spawn_enemy`, and parses back
+/// from it, so a log written to a file can be loaded with [`Replay::from_log`].
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Draw {
+    /// The tag that was set on the RNG when the call was made, see [`Recording::set_tag`].
+    pub tag: Option<Cow<'static, str>>,
+    /// What the call returned.
+    pub value: DrawValue,
+}
+
+impl fmt::Display for Draw {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.value {
+            DrawValue::U32(value) => write!(f, "u32 {value:#x}")?,
+            DrawValue::U64(value) => write!(f, "u64 {value:#x}")?,
+            DrawValue::Bytes(ref bytes) => {
+                f.write_str("bytes ")?;
+                for byte in bytes {
+                    write!(f, "{byte:02x}")?;
+                }
+            }
+        }
+        self.tag.as_ref().map_or(Ok(()), |tag| write!(f, " @{tag}"))
+    }
+}
+
+impl FromStr for Draw {
+    type Err = RngError;
+
+    /// Parse a line of a text log, see [`Draw`].
+    #[inline]
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        const INVALID: RngError = RngError::InvalidLog("expected `u32 0x..`, `u64 0x..` or `bytes ..`, optionally followed by ` @tag`");
+
+        let (draw, tag) = match line.trim().split_once(" @") {
+            Some((draw, tag)) => (draw, Some(Cow::Owned(String::from(tag)))),
+            None => (line.trim(), None),
+        };
+        // a zero-length `fill_bytes` without a tag ends in `bytes `, which loses its space to the trim
+        let (kind, value) = draw.split_once(' ').unwrap_or((draw, ""));
+        let value = value.trim();
+        let hex = value.strip_prefix("0x").unwrap_or(value);
+
+        let value = match kind {
+            "u32" => DrawValue::U32(u32::from_str_radix(hex, 16).map_err(|_| INVALID)?),
+            "u64" => DrawValue::U64(u64::from_str_radix(hex, 16).map_err(|_| INVALID)?),
+            "bytes" => {
+                let digits = value.as_bytes();
+                if digits.len() % 2 != 0 {
+                    return Err(INVALID);
+                }
+                let mut bytes = Vec::with_capacity(digits.len() / 2);
+                for pair in digits.chunks_exact(2) {
+                    let pair = str::from_utf8(pair).map_err(|_| INVALID)?;
+                    bytes.push(u8::from_str_radix(pair, 16).map_err(|_| INVALID)?);
+                }
+                DrawValue::Bytes(bytes)
+            }
+            _ => return Err(INVALID),
+        };
+        Ok(Self { tag, value })
+    }
+}
+
+/// Where a [`Recording`] logs its draws.
+///
+/// Implemented for `Vec<Draw>`, to keep the log in memory, and for [`WriteLog`], to write it
+/// out as text.
+pub trait DrawSink {
+    /// Log a draw.
+    fn record(&mut self, draw: Draw);
+}
+
+impl DrawSink for Vec<Draw> {
+    #[inline]
+    fn record(&mut self, draw: Draw) {
+        self.push(draw);
+    }
+}
+
+/// Writes every draw as a line of text to an [`io::Write`], see [`Draw`] for the format.
+///
+/// Drawing from an RNG can't fail, so the first write error is kept and every later draw is
+/// dropped. Check it with [`WriteLog::into_inner`].
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct WriteLog<W> {
+    writer: W,
+    error: Option<io::Error>,
+}
+
+#[cfg(feature = "std")]
+impl<W: io::Write> WriteLog<W> {
+    /// Log to `writer`.
+    #[inline]
+    #[must_use]
+    pub const fn new(writer: W) -> Self {
+        Self { writer, error: None }
+    }
+
+    /// The writer, or the first error writing to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error writing a draw.
+    #[inline]
+    pub fn into_inner(self) -> io::Result<W> {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok(self.writer),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: io::Write> DrawSink for WriteLog<W> {
+    #[inline]
+    fn record(&mut self, draw: Draw) {
+        if self.error.is_none() {
+            self.error = writeln!(self.writer, "{draw}").err();
+        }
+    }
+}
+
+/// Wraps an RNG and logs every call to it, to find out exactly which draws happened.
+///
+/// Implements [`RngCore`] and [`RngCoreExtension`](crate::rng_core_extension::RngCoreExtension), so it can be passed to the shuffle and
+/// select APIs in place of the RNG it wraps. The extension methods are built on
+/// [`RngCore::fill_bytes`], so each of them is logged as a single `bytes` draw.
+///
+/// ```rust
+/// use voxell_rng::prelude::*;
+/// use voxell_rng::recording::{Recording, Replay};
+/// use voxell_rng::rng::XorShift128;
+///
+/// let mut rng = Recording::new(XorShift128::new(1));
+/// rng.set_tag(Some("deal"));
+/// let mut deck = [1, 2, 3, 4, 5];
+/// deck.shuffle_with(&mut rng);
+///
+/// // replaying the same calls reproduces the shuffle
+/// let (_, log) = rng.into_parts();
+/// let mut replay = Replay::new(log.clone());
+/// let mut again = [1, 2, 3, 4, 5];
+/// again.shuffle_with(&mut replay);
+/// assert_eq!(again, deck);
+/// assert!(replay.divergence().is_none());
+///
+/// // a run that draws differently is caught at its first differing call
+/// let mut replay = Replay::new(log);
+/// let _ = replay.next_u32();
+/// let divergence = replay.divergence().unwrap();
+/// assert_eq!(divergence.index, 0);
+/// assert_eq!(divergence.expected.as_ref().unwrap().tag.as_deref(), Some("deal"));
+/// ```
+pub struct Recording<R, S = Vec<Draw>> {
+    rng: R,
+    sink: S,
+    tag: Option<&'static str>,
+}
+
+impl<R: RngCore> Recording<R> {
+    /// Log the draws of `rng` in memory.
+    #[inline]
+    #[must_use]
+    pub const fn new(rng: R) -> Self {
+        Self::with_sink(rng, Vec::new())
+    }
+
+    /// The draws logged so far.
+    #[inline]
+    #[must_use]
+    pub fn log(&self) -> &[Draw] {
+        &self.sink
+    }
+}
+
+impl<R: RngCore, S: DrawSink> Recording<R, S> {
+    /// Log the draws of `rng` to `sink`.
+    #[inline]
+    #[must_use]
+    pub const fn with_sink(rng: R, sink: S) -> Self {
+        Self { rng, sink, tag: None }
+    }
+
+    /// Tag the draws from now on, to tell which part of the program made them. `None` removes
+    /// the tag.
+    #[inline]
+    pub const fn set_tag(&mut self, tag: Option<&'static str>) {
+        self.tag = tag;
+    }
+
+    /// The wrapped RNG.
+    #[inline]
+    #[must_use]
+    pub const fn inner(&self) -> &R {
+        &self.rng
+    }
+
+    /// Stop recording, and get the RNG and the sink back.
+    #[inline]
+    #[must_use]
+    pub fn into_parts(self) -> (R, S) {
+        (self.rng, self.sink)
+    }
+
+    #[inline]
+    fn record(&mut self, value: DrawValue) {
+        self.sink.record(Draw {
+            tag: self.tag.map(Cow::Borrowed),
+            value,
+        });
+    }
+}
+
+impl<R: RngCore, S: DrawSink> RngCore for Recording<R, S> {
+    /// Fill `dest` with random data from the wrapped RNG, and log it.
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest);
+        self.record(DrawValue::Bytes(dest.to_vec()));
+    }
+
+    /// Return the next random `u32` from the wrapped RNG, and log it.
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        let value = self.rng.next_u32();
+        self.record(DrawValue::U32(value));
+        value
+    }
+
+    /// Return the next random `u64` from the wrapped RNG, and log it.
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let value = self.rng.next_u64();
+        self.record(DrawValue::U64(value));
+        value
+    }
+}
+
+/// The first call to a [`Replay`] that didn't match the log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    /// The position of the call in the log.
+    pub index: usize,
+    /// The draw the log has at that position, `None` if the log was exhausted.
+    pub expected: Option<Draw>,
+    /// The call that was made instead.
+    pub actual: DrawKind,
+    /// The tag that was set on the [`Replay`] when the call was made.
+    pub tag: Option<&'static str>,
+}
+
+impl fmt::Display for Divergence {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "draw #{}: called {}", self.index, self.actual)?;
+        if let Some(tag) = self.tag {
+            write!(f, " @{tag}")?;
+        }
+        match self.expected {
+            Some(ref draw) => write!(f, ", but the log has `{draw}`"),
+            None => f.write_str(", but the log is exhausted"),
+        }
+    }
+}
+
+/// An RNG that returns the draws logged by a [`Recording`], in order.
+///
+/// Every call is checked against the log: the kind of call and, for `fill_bytes`, the length of
+/// the buffer must match. The first call that doesn't match is kept as a [`Divergence`]; from
+/// then on the draws come from a fixed [`SplitMix64`] so the program can keep running.
+pub struct Replay {
+    log: Vec<Draw>,
+    next: usize,
+    divergence: Option<Divergence>,
+    fallback: SplitMix64,
+    tag: Option<&'static str>,
+}
+
+impl Replay {
+    /// Replay the draws in `log`.
+    #[inline]
+    #[must_use]
+    pub const fn new(log: Vec<Draw>) -> Self {
+        Self {
+            log,
+            next: 0,
+            divergence: None,
+            fallback: SplitMix64::wrap(0),
+            tag: None,
+        }
+    }
+
+    /// Replay a text log, one [`Draw`] per line. Blank lines are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RngError::InvalidLog`] if a line is not a valid draw.
+    #[inline]
+    pub fn from_log(log: &str) -> Result<Self, RngError> {
+        let log = log
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+        Ok(Self::new(log))
+    }
+
+    /// Tag the calls from now on, the tag is reported in a [`Divergence`].
+    #[inline]
+    pub const fn set_tag(&mut self, tag: Option<&'static str>) {
+        self.tag = tag;
+    }
+
+    /// The first call that didn't match the log, if there was one.
+    #[inline]
+    #[must_use]
+    pub const fn divergence(&self) -> Option<&Divergence> {
+        self.divergence.as_ref()
+    }
+
+    /// Whether every draw in the log was replayed without a divergence.
+    #[inline]
+    #[must_use]
+    pub const fn is_finished(&self) -> bool {
+        self.divergence.is_none() && self.next == self.log.len()
+    }
+
+    /// The next draw in the log, if it is of the `actual` kind.
+    #[inline]
+    fn take(&mut self, actual: DrawKind) -> Option<&DrawValue> {
+        if self.divergence.is_some() {
+            return None;
+        }
+
+        let index = self.next;
+        match self.log.get(index) {
+            Some(draw) if draw.value.kind() == actual => {
+                self.next += 1;
+                Some(&draw.value)
+            }
+            expected => {
+                self.divergence = Some(Divergence {
+                    index,
+                    expected: expected.cloned(),
+                    actual,
+                    tag: self.tag,
+                });
+                None
+            }
+        }
+    }
+}
+
+impl RngCore for Replay {
+    /// Fill `dest` with the next logged bytes.
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self.take(DrawKind::Bytes(dest.len())).and_then(DrawValue::bytes) {
+            Some(bytes) => dest.copy_from_slice(bytes),
+            None => self.fallback.fill_bytes(dest),
+        }
+    }
+
+    /// Return the next logged `u32`.
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        match self.take(DrawKind::U32) {
+            Some(&DrawValue::U32(value)) => value,
+            _ => RngCore::next_u32(&mut self.fallback),
+        }
+    }
+
+    /// Return the next logged `u64`.
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        match self.take(DrawKind::U64) {
+            Some(&DrawValue::U64(value)) => value,
+            _ => RngCore::next_u64(&mut self.fallback),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cow, Draw, DrawValue};
+
+    #[cfg(not(feature = "std"))]
+    use alloc::{string::ToString, vec::Vec};
+
+    #[test]
+    fn draws_round_trip_through_text() {
+        let draws = [
+            Draw {
+                tag: None,
+                value: DrawValue::U32(0x2a),
+            },
+            Draw {
+                tag: Some(Cow::Borrowed("spawn_enemy")),
+                value: DrawValue::U64(u64::MAX),
+            },
+            Draw {
+                tag: None,
+                value: DrawValue::Bytes(Vec::from([0x00, 0xab, 0xff])),
+            },
+            Draw {
+                tag: None,
+                value: DrawValue::Bytes(Vec::new()),
+            },
+            Draw {
+                tag: Some(Cow::Borrowed("loot")),
+                value: DrawValue::Bytes(Vec::new()),
+            },
+        ];
+        for draw in draws {
+            let line = draw.to_string();
+            assert_eq!(
+                line.parse::<Draw>().ok(),
+                Some(draw),
+                "`{line}` should parse back to the draw it came from"
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_lines_are_rejected() {
+        for line in ["", "u32", "u64 ", "u32 0x1ffffffff", "bytes abc", "bytes zz", "f64 0x0"] {
+            assert!(line.parse::<Draw>().is_err(), "`{line}` should be rejected");
+        }
+    }
+}
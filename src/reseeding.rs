@@ -0,0 +1,193 @@
+use crate::branch_rng::BranchRng;
+use crate::entropy::{EntropySource, GlobalEntropy, TryFromEntropy};
+use crate::RngError;
+use rand_core::{RngCore, SeedableRng};
+
+#[cfg(feature = "std")]
+use crate::fork;
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+/// Wraps an engine and reseeds it from an entropy source every so often.
+///
+/// The engine is replaced with a new one seeded from `source` before a draw once `threshold`
+/// bytes have been drawn since the last reseed, and with `std`, once the interval set with
+/// [`Reseeding::with_interval`] has passed or the process has forked since the last reseed.
+/// A `threshold` of 0 never reseeds based on the bytes drawn.
+///
+/// If the source fails, the engine keeps its current state and the reseed is tried again on
+/// the next schedule. Use [`Reseeding::reseed`] to see the error.
+///
+/// ```rust
+/// # #[cfg(feature = "std")] {
+/// use std::time::Duration;
+/// use voxell_rng::entropy::GlobalEntropy;
+/// use voxell_rng::prelude::*;
+/// use voxell_rng::reseeding::Reseeding;
+/// use voxell_rng::rng::XorShift128;
+///
+/// let mut rng = Reseeding::new(XorShift128::default(), 1 << 20, GlobalEntropy).with_interval(Duration::from_secs(60));
+/// let jitter_ms = rng.next_bounded_u64(250);
+/// # }
+/// ```
+///
+/// A source that always returns the same bytes makes the schedule visible:
+///
+/// ```rust
+/// use rand_core::{RngCore, SeedableRng};
+/// use voxell_rng::reseeding::Reseeding;
+/// use voxell_rng::rng::XorShift128;
+/// use voxell_rng::RngError;
+///
+/// let source = |dest: &mut [u8]| {
+///     dest.fill(7);
+///     Ok::<_, RngError>(())
+/// };
+/// let mut rng = Reseeding::new(XorShift128::from_seed([7; 16]), 16, source);
+/// let first = [rng.next_u64(), rng.next_u64()];
+///
+/// // 16 bytes were drawn, so the next draw starts over from a fresh seed
+/// assert_eq!([rng.next_u64(), rng.next_u64()], first);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Reseeding<R, S = GlobalEntropy> {
+    rng: R,
+    source: S,
+    threshold: u64,
+    bytes_until_reseed: u64,
+    #[cfg(feature = "std")]
+    interval: Option<Duration>,
+    #[cfg(feature = "std")]
+    last_reseed: Option<Instant>,
+    #[cfg(feature = "std")]
+    fork_generation: usize,
+}
+
+impl<R, S> Reseeding<R, S>
+where
+    R: RngCore + SeedableRng,
+    S: EntropySource,
+{
+    /// Reseed `rng` from `source` after every `threshold` bytes drawn from it.
+    #[inline]
+    #[must_use]
+    #[cfg_attr(
+        not(feature = "std"),
+        allow(clippy::missing_const_for_fn, reason = "reads the fork generation with `std`")
+    )]
+    pub fn new(rng: R, threshold: u64, source: S) -> Self {
+        Self {
+            rng,
+            source,
+            threshold,
+            bytes_until_reseed: threshold,
+            #[cfg(feature = "std")]
+            interval: None,
+            #[cfg(feature = "std")]
+            last_reseed: None,
+            #[cfg(feature = "std")]
+            fork_generation: fork::generation(),
+        }
+    }
+
+    /// Also reseed once `interval` has passed since the last reseed, counting from now.
+    #[cfg(feature = "std")]
+    #[inline]
+    #[must_use]
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self.last_reseed = Some(Instant::now());
+        self
+    }
+
+    /// Reseed the engine right away, and start the schedule over.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entropy source fails, the engine keeps its current state in
+    /// that case.
+    #[inline]
+    pub fn reseed(&mut self) -> Result<(), RngError> {
+        self.bytes_until_reseed = self.threshold;
+        #[cfg(feature = "std")]
+        {
+            self.last_reseed = self.interval.map(|_| Instant::now());
+            self.fork_generation = fork::generation();
+        }
+
+        self.rng = R::try_from_source(&mut self.source)?;
+        Ok(())
+    }
+
+    /// The engine being reseeded.
+    #[inline]
+    #[must_use]
+    pub const fn inner(&self) -> &R {
+        &self.rng
+    }
+
+    /// Reseed if it's due, and count `bytes` towards the threshold.
+    #[inline]
+    fn before_draw(&mut self, bytes: usize) {
+        let due = self.threshold != 0 && self.bytes_until_reseed == 0;
+        #[cfg(feature = "std")]
+        let due = due
+            || self.fork_generation != fork::generation()
+            || self
+                .interval
+                .zip(self.last_reseed)
+                .is_some_and(|(interval, last_reseed)| last_reseed.elapsed() >= interval);
+
+        if due {
+            // a failed reseed is tried again on the next schedule
+            let _ = self.reseed();
+        }
+        self.bytes_until_reseed = self.bytes_until_reseed.saturating_sub(bytes as u64);
+    }
+}
+
+impl<R, S> RngCore for Reseeding<R, S>
+where
+    R: RngCore + SeedableRng,
+    S: EntropySource,
+{
+    /// Fill `dest` with random data, reseeding first if it's due.
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.before_draw(dest.len());
+        self.rng.fill_bytes(dest);
+    }
+
+    /// Return the next random `u32`, reseeding first if it's due.
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.before_draw(4);
+        self.rng.next_u32()
+    }
+
+    /// Return the next random `u64`, reseeding first if it's due.
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.before_draw(8);
+        self.rng.next_u64()
+    }
+}
+
+impl<R, S> BranchRng<Self> for Reseeding<R, S>
+where
+    R: RngCore + SeedableRng + BranchRng<R>,
+    S: EntropySource + Clone,
+{
+    /// Branch the engine, the branch shares the schedule but starts it over.
+    #[inline]
+    fn branch_rng(&mut self) -> Self {
+        self.before_draw(0);
+        let branch = Self::new(self.rng.branch_rng(), self.threshold, self.source.clone());
+        #[cfg(feature = "std")]
+        let branch = match self.interval {
+            Some(interval) => branch.with_interval(interval),
+            None => branch,
+        };
+        branch
+    }
+}
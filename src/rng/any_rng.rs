@@ -0,0 +1,429 @@
+use super::pcg_advanced::pcg_128::PcgInnerStateSetseq128;
+use super::pcg_advanced::pcg_16::PcgInnerStateSetseq16;
+use super::pcg_advanced::pcg_32::PcgInnerStateSetseq32;
+use super::pcg_advanced::pcg_64::PcgInnerStateSetseq64;
+use super::pcg_advanced::pcg_8::PcgInnerStateSetseq8;
+use super::{Pcg128, Pcg16, Pcg32, Pcg64, Pcg8, SplitMix64, XoRoShiRo128, XorShift128, XorShift32};
+use crate::branch_rng::BranchRng;
+use crate::error::RngError;
+use crate::sample::Random;
+use crate::snapshot::{EngineTag, Snapshot};
+use crate::stateful::StatefulRng;
+use core::fmt;
+use core::str::FromStr;
+use rand_core::{RngCore, SeedableRng};
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// any of the engines in [`rng`](crate::rng) and [`pcg_advanced`](crate::rng::pcg_advanced), picked at runtime
+///
+/// the engines of `pcg_advanced` are the multi-stream `PcgInnerStateSetseq*` states, the other
+/// `pcg_advanced` states are what the `Pcg*` engines step
+///
+/// an engine can be described by a spec string, `<engine>:<key>=<value>,...`, where the engine is
+/// one of `splitmix64`, `xorshift32`, `xorshift128`, `xoroshiro128`, `pcg8`, `pcg16`, `pcg32`,
+/// `pcg64` and `pcg128`, and the keys are:
+///
+/// - `seed=<u64>`: seed the engine with [`SeedableRng::seed_from_u64`]
+/// - `stream=<int>`: together with `seed`, pick the stream of a PCG engine, which makes it a
+///   `PcgInnerStateSetseq*`
+/// - `state=<word>,<word>`: the exact state of the engine, as returned by [`StatefulRng::state`].
+///   Two words for a PCG engine are its state and increment, which makes it a `PcgInnerStateSetseq*`
+///
+/// numbers are decimal, or hexadecimal with a `0x` prefix
+///
+/// the engine displays as its current state in the same syntax, so it can be pasted into a bug
+/// report and parsed back into an engine that continues with the same output
+///
+/// ```rust
+/// use voxell_rng::prelude::*;
+/// use voxell_rng::rng::AnyRng;
+///
+/// let mut rng: AnyRng = "pcg64:seed=123,stream=7".parse().unwrap();
+/// let _ = rng.next_u64();
+///
+/// let spec = rng.to_string();
+/// assert!(spec.starts_with("pcg64:state=0x"));
+///
+/// let mut resumed: AnyRng = spec.parse().unwrap();
+/// assert_eq!(rng.next_u64(), resumed.next_u64());
+///
+/// let rng: AnyRng = "xoroshiro128:state=0x1,0x2".parse().unwrap();
+/// assert_eq!(rng.to_string(), "xoroshiro128:state=0x1,0x2");
+/// ```
+#[derive(PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AnyRng {
+    /// [`SplitMix64`]
+    SplitMix64(SplitMix64),
+    /// [`XorShift32`]
+    XorShift32(XorShift32),
+    /// [`XorShift128`]
+    XorShift128(XorShift128),
+    /// [`XoRoShiRo128`]
+    XoRoShiRo128(XoRoShiRo128),
+    /// [`Pcg8`]
+    Pcg8(Pcg8),
+    /// [`Pcg16`]
+    Pcg16(Pcg16),
+    /// [`Pcg32`]
+    Pcg32(Pcg32),
+    /// [`Pcg64`]
+    Pcg64(Pcg64),
+    /// [`Pcg128`]
+    Pcg128(Pcg128),
+    /// [`PcgInnerStateSetseq8`]
+    PcgInnerStateSetseq8(PcgInnerStateSetseq8),
+    /// [`PcgInnerStateSetseq16`]
+    PcgInnerStateSetseq16(PcgInnerStateSetseq16),
+    /// [`PcgInnerStateSetseq32`]
+    PcgInnerStateSetseq32(PcgInnerStateSetseq32),
+    /// [`PcgInnerStateSetseq64`]
+    PcgInnerStateSetseq64(PcgInnerStateSetseq64),
+    /// [`PcgInnerStateSetseq128`]
+    PcgInnerStateSetseq128(PcgInnerStateSetseq128),
+}
+
+macro_rules! any_rng_engines {
+    ($($engine:ident => $name:literal),* $(,)?) => {
+        impl AnyRng {
+            /// the tag of the engine inside
+            #[inline]
+            #[must_use]
+            pub const fn tag(&self) -> EngineTag {
+                match *self {
+                    $(Self::$engine(_) => EngineTag::$engine,)*
+                }
+            }
+
+            /// encode the engine inside with [`Snapshot::to_bytes`]
+            #[inline]
+            #[must_use]
+            pub fn to_bytes(&self) -> Vec<u8> {
+                match *self {
+                    $(Self::$engine(ref rng) => rng.to_bytes(),)*
+                }
+            }
+
+            /// restore whichever engine the snapshot was taken from, according to its tag
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the snapshot is invalid, see [`Snapshot::from_bytes`].
+            #[inline]
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, RngError> {
+                match EngineTag::of(bytes)? {
+                    $(EngineTag::$engine => $engine::from_bytes(bytes).map(Self::$engine),)*
+                }
+            }
+        }
+
+        #[cfg(not(feature = "strict"))]
+        impl Clone for AnyRng {
+            #[inline]
+            #[track_caller]
+            fn clone(&self) -> Self {
+                match *self {
+                    $(Self::$engine(ref rng) => Self::$engine(rng.clone()),)*
+                }
+            }
+        }
+
+        impl RngCore for AnyRng {
+            /// Fill `dest` with random data.
+            #[inline]
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                match *self {
+                    $(Self::$engine(ref mut rng) => rng.fill_bytes(dest),)*
+                }
+            }
+
+            /// Return the next random `u32`.
+            #[inline]
+            fn next_u32(&mut self) -> u32 {
+                match *self {
+                    $(Self::$engine(ref mut rng) => RngCore::next_u32(rng),)*
+                }
+            }
+
+            /// Return the next random `u64`.
+            #[inline]
+            fn next_u64(&mut self) -> u64 {
+                match *self {
+                    $(Self::$engine(ref mut rng) => RngCore::next_u64(rng),)*
+                }
+            }
+        }
+
+        impl BranchRng<Self> for AnyRng {
+            #[inline]
+            fn branch_rng(&mut self) -> Self {
+                match *self {
+                    $(Self::$engine(ref mut rng) => Self::$engine(rng.branch_rng()),)*
+                }
+            }
+        }
+
+        impl fmt::Display for AnyRng {
+            /// the spec of the current state, see [`AnyRng`]
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match *self {
+                    $(Self::$engine(ref rng) => {
+                        write!(f, "{}:state=", $name)?;
+                        rng.state().write_words(f)
+                    })*
+                }
+            }
+        }
+
+        $(
+            impl From<$engine> for AnyRng {
+                #[inline]
+                fn from(rng: $engine) -> Self {
+                    Self::$engine(rng)
+                }
+            }
+        )*
+    };
+}
+
+any_rng_engines!(
+    SplitMix64 => "splitmix64",
+    XorShift32 => "xorshift32",
+    XorShift128 => "xorshift128",
+    XoRoShiRo128 => "xoroshiro128",
+    Pcg8 => "pcg8",
+    Pcg16 => "pcg16",
+    Pcg32 => "pcg32",
+    Pcg64 => "pcg64",
+    Pcg128 => "pcg128",
+    PcgInnerStateSetseq8 => "pcg8",
+    PcgInnerStateSetseq16 => "pcg16",
+    PcgInnerStateSetseq32 => "pcg32",
+    PcgInnerStateSetseq64 => "pcg64",
+    PcgInnerStateSetseq128 => "pcg128",
+);
+
+/// The state words of an engine, written in a spec.
+trait SpecWords {
+    fn write_words(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+macro_rules! impl_spec_words {
+    ($($word:ty),*) => {
+        $(
+            impl SpecWords for $word {
+                #[inline]
+                fn write_words(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    write!(f, "{self:#x}")
+                }
+            }
+
+            impl SpecWords for [$word; 2] {
+                #[inline]
+                fn write_words(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    let &[state, inc] = self;
+                    write!(f, "{state:#x},{inc:#x}")
+                }
+            }
+        )*
+    };
+}
+
+impl_spec_words!(u8, u16, u32, u64, u128);
+
+/// How a spec asks for the engine to be initialized.
+#[derive(Clone, Copy)]
+enum Init<'s> {
+    Seed(u64),
+    Stream(u64, u128),
+    State(&'s [u128]),
+}
+
+impl FromStr for AnyRng {
+    type Err = RngError;
+
+    /// Parse a spec, see [`AnyRng`] for the syntax.
+    #[inline]
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let Some((name, params)) = spec.trim().split_once(':') else {
+            return Err(RngError::InvalidSpec("expected `<engine>:<key>=<value>,...`"));
+        };
+
+        let mut seed = None;
+        let mut stream = None;
+        let mut state = [0; 2];
+        let mut state_len = 0;
+        let mut key = "";
+        for param in params.split(',').map(str::trim) {
+            let value = match param.split_once('=') {
+                Some((new_key, value)) => {
+                    key = new_key.trim();
+                    value.trim()
+                }
+                None => param,
+            };
+            let value = parse_int(value)?;
+            match key {
+                "seed" if seed.is_none() => seed = Some(u64::try_from(value).map_err(|_| RngError::InvalidSpec("`seed` doesn't fit in a u64"))?),
+                "stream" if stream.is_none() => stream = Some(value),
+                "state" if state_len < state.len() => {
+                    state[state_len] = value;
+                    state_len += 1;
+                }
+                "seed" | "stream" | "state" => return Err(RngError::InvalidSpec("too many values for a key")),
+                _ => return Err(RngError::InvalidSpec("unknown key, expected `seed`, `stream` or `state`")),
+            }
+        }
+
+        let words = state.get(..state_len).unwrap_or_default();
+        let init = match (seed, stream) {
+            (None, None) if !words.is_empty() => Init::State(words),
+            _ if !words.is_empty() => return Err(RngError::InvalidSpec("`state` can't be combined with `seed` or `stream`")),
+            (Some(seed), None) => Init::Seed(seed),
+            (Some(seed), Some(stream)) => Init::Stream(seed, stream),
+            (None, Some(_)) => return Err(RngError::InvalidSpec("`stream` needs a `seed`")),
+            (None, None) => return Err(RngError::InvalidSpec("expected a `seed` or a `state`")),
+        };
+
+        match name.trim() {
+            "splitmix64" => simple_from_spec(init, SplitMix64::seed_from_u64, |[x]| Ok(SplitMix64::wrap(x))),
+            "xorshift32" => simple_from_spec(init, XorShift32::seed_from_u64, |[x]| XorShift32::try_wrap(x)),
+            "xorshift128" => simple_from_spec(init, XorShift128::seed_from_u64, XorShift128::try_wrap),
+            "xoroshiro128" => simple_from_spec(init, XoRoShiRo128::seed_from_u64, XoRoShiRo128::try_wrap),
+            "pcg8" => pcg_from_spec(init, Pcg8::seed_from_u64, Pcg8::from_state_word, PcgInnerStateSetseq8::setseq_seeded),
+            "pcg16" => pcg_from_spec(init, Pcg16::seed_from_u64, Pcg16::from_state_word, PcgInnerStateSetseq16::setseq_seeded),
+            "pcg32" => pcg_from_spec(init, Pcg32::seed_from_u64, Pcg32::from_state_word, PcgInnerStateSetseq32::setseq_seeded),
+            "pcg64" => pcg_from_spec(init, Pcg64::seed_from_u64, Pcg64::from_state_word, PcgInnerStateSetseq64::setseq_seeded),
+            "pcg128" => pcg_from_spec(
+                init,
+                Pcg128::seed_from_u64,
+                Pcg128::from_state_word,
+                PcgInnerStateSetseq128::setseq_seeded,
+            ),
+            _ => Err(RngError::InvalidSpec("unknown engine")),
+        }
+    }
+}
+
+#[inline]
+fn parse_int(value: &str) -> Result<u128, RngError> {
+    let hex = value.strip_prefix("0x").or_else(|| value.strip_prefix("0X"));
+    let parsed = hex.map_or_else(|| value.parse(), |hex| u128::from_str_radix(hex, 16));
+    parsed.map_err(|_| RngError::InvalidSpec("expected a decimal or `0x` hexadecimal number"))
+}
+
+/// Narrow the state words of a spec to the `N` words of an engine.
+#[inline]
+fn state_words<W, const N: usize>(words: &[u128]) -> Result<[W; N], RngError>
+where
+    W: TryFrom<u128> + Copy + Default,
+{
+    if words.len() != N {
+        return Err(RngError::InvalidSpec("wrong number of state words for the engine"));
+    }
+
+    let mut narrowed = [W::default(); N];
+    for (slot, &word) in narrowed.iter_mut().zip(words) {
+        *slot = W::try_from(word).map_err(|_| RngError::InvalidSpec("a state word is too large for the engine"))?;
+    }
+    Ok(narrowed)
+}
+
+/// An engine without streams.
+#[inline]
+fn simple_from_spec<R, W, const N: usize>(
+    init: Init<'_>,
+    seeded: impl FnOnce(u64) -> R,
+    from_state: impl FnOnce([W; N]) -> Result<R, RngError>,
+) -> Result<AnyRng, RngError>
+where
+    R: Into<AnyRng>,
+    W: TryFrom<u128> + Copy + Default,
+{
+    let rng = match init {
+        Init::Seed(seed) => seeded(seed),
+        Init::Stream(..) => return Err(RngError::InvalidSpec("only the PCG engines have streams")),
+        Init::State(words) => from_state(state_words(words)?)?,
+    };
+    Ok(rng.into())
+}
+
+/// A PCG engine, which becomes a `PcgInnerStateSetseq*` when a stream or an increment is given.
+#[inline]
+fn pcg_from_spec<P, S, W>(
+    init: Init<'_>,
+    seeded: impl FnOnce(u64) -> P,
+    from_state: impl FnOnce(W) -> P,
+    setseq_seeded: impl FnOnce(W, W) -> S,
+) -> Result<AnyRng, RngError>
+where
+    P: Into<AnyRng>,
+    S: Into<AnyRng> + TryFrom<[W; 2], Error = RngError>,
+    W: TryFrom<u128> + Copy + Default + Random,
+{
+    Ok(match init {
+        Init::Seed(seed) => seeded(seed).into(),
+        Init::Stream(seed, stream) => {
+            let initstate = W::random(&mut SplitMix64::wrap(seed));
+            let Ok(initseq) = W::try_from(stream) else {
+                return Err(RngError::InvalidSpec("`stream` is too large for the engine"));
+            };
+            setseq_seeded(initstate, initseq).into()
+        }
+        Init::State(words) if words.len() == 1 => {
+            let [state] = state_words(words)?;
+            from_state(state).into()
+        }
+        Init::State(words) => S::try_from(state_words(words)?)?.into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AnyRng, XoRoShiRo128};
+    use crate::branch_rng::BranchRng;
+    use crate::stateful::StatefulRng;
+    use rand_core::RngCore;
+
+    #[cfg(not(feature = "std"))]
+    use alloc::string::ToString;
+
+    #[test]
+    fn spec_round_trips_a_zero_first_word() {
+        let spec = "xoroshiro128:state=0x0,0x1";
+        let mut rng: AnyRng = spec.parse().unwrap();
+        assert_eq!(rng.to_string(), spec, "the spec should print as it was parsed");
+
+        let mut expected = XoRoShiRo128::from_state([0, 1]).unwrap();
+        assert_eq!(
+            rng.next_u64(),
+            expected.next_u64(),
+            "the parsed engine should start from the spec's state"
+        );
+
+        let mut reparsed: AnyRng = rng.to_string().parse().unwrap();
+        assert_eq!(reparsed.next_u64(), rng.next_u64(), "a printed spec should resume the engine");
+    }
+
+    #[test]
+    fn pcg_stream_specs_branch_apart() {
+        let mut rng: AnyRng = "pcg64:seed=1,stream=2".parse().unwrap();
+        let mut first = rng.branch_rng();
+        let mut second = rng.branch_rng();
+        let draws = [first.next_u64(), second.next_u64(), rng.next_u64()];
+        assert_ne!(draws[0], draws[1], "two branches should draw different numbers");
+        assert_ne!(draws[0], draws[2], "a branch should draw different numbers than its parent");
+        assert_ne!(draws[1], draws[2], "a branch should draw different numbers than its parent");
+    }
+
+    #[test]
+    fn spec_rejects_the_all_zero_state() {
+        assert!(
+            "xoroshiro128:state=0x0,0x0".parse::<AnyRng>().is_err(),
+            "an all-zero xoroshiro128 state should be rejected"
+        );
+    }
+}
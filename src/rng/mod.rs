@@ -42,11 +42,13 @@ pub mod pcg_advanced {
     }
 }
 
+mod any_rng;
 mod splitmix64;
 mod xoroshiro128;
 mod xorshift128;
 mod xorshift32;
 
+pub use any_rng::AnyRng;
 pub use splitmix64::SplitMix64;
 pub use xoroshiro128::XoRoShiRo128;
 pub use xorshift128::XorShift128;
@@ -8,23 +8,33 @@
 
 use core::{ffi::CStr, mem::MaybeUninit, ptr};
 
-use rand_core::RngCore;
+use rand_core::{RngCore, SeedableRng};
 
 use crate::branch_rng::BranchRng;
+use crate::lineage::{self, impl_tracked_clone};
+use crate::sample::Random;
+use crate::RngError;
 
-use super::polyfill::polyfill_fill_bytes_u128;
+use super::{polyfill::polyfill_fill_bytes_u128, SplitMix64};
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct Pcg128 {
     state: PcgInnerState128,
 }
 
+impl_tracked_clone! {
+    Pcg128 { state };
+}
+
 impl BranchRng<Self> for Pcg128 {
+    /// There is a single stream, so the branch is seeded with a draw from `self` instead.
     #[inline]
     fn branch_rng(&mut self) -> Self {
-        let mut oldstate = self.state.clone();
-        PcgInnerState128::oneseq_advance(&mut oldstate, 1);
-        Self { state: oldstate }
+        let initstate = u128::random(self);
+        Self {
+            state: PcgInnerState128::oneseq_seeded(initstate),
+        }
     }
 }
 
@@ -38,6 +48,22 @@ impl Default for Pcg128 {
 }
 
 impl Pcg128 {
+    /// the raw LCG state
+    #[inline]
+    #[must_use]
+    pub(crate) const fn state_word(&self) -> u128 {
+        self.state.state
+    }
+
+    /// wrap a raw LCG state, as returned by `state_word`
+    #[inline]
+    #[must_use]
+    pub(crate) const fn from_state_word(state: u128) -> Self {
+        Self {
+            state: PcgInnerState128 { state },
+        }
+    }
+
     #[inline]
     #[must_use]
     pub fn new(seed: u128) -> Self {
@@ -59,30 +85,128 @@ impl Pcg128 {
 impl RngCore for Pcg128 {
     #[inline]
     fn fill_bytes(&mut self, dest: &mut [u8]) {
+        lineage::stepped(self);
         polyfill_fill_bytes_u128(Self::default_advance)(self, dest);
     }
 
     #[inline]
     fn next_u32(&mut self) -> u32 {
+        lineage::stepped(self);
         self.default_small_advance() as u32
     }
     #[inline]
     fn next_u64(&mut self) -> u64 {
+        lineage::stepped(self);
         self.default_small_advance()
     }
 }
 
+impl SeedableRng for Pcg128 {
+    type Seed = [u8; 16];
+
+    /// Create a new `Pcg128` from the little-endian bytes of its initial state.
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self {
+            state: PcgInnerState128::oneseq_seeded(u128::from_le_bytes(seed)),
+        }
+    }
+
+    /// Create a new `Pcg128` using a `SplitMix64` RNG to expand `state` into a seed.
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_rng(&mut SplitMix64::wrap(state))
+    }
+}
+
 #[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct PcgInnerState128 {
     state: u128,
 }
 
-#[derive(Clone, PartialEq, Eq)]
+/// serializes as `[state, inc]`, the increment has to be odd
+#[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(try_from = "[u128; 2]"))]
 pub struct PcgInnerStateSetseq128 {
     state: u128,
     inc: u128,
 }
 
+impl_tracked_clone! {
+    PcgInnerStateSetseq128 { state, inc };
+}
+
+impl TryFrom<[u128; 2]> for PcgInnerStateSetseq128 {
+    type Error = RngError;
+
+    /// Create the state from `[state, inc]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RngError::InvalidSeed`] if `inc` is even.
+    #[inline]
+    fn try_from([state, inc]: [u128; 2]) -> Result<Self, Self::Error> {
+        if inc & 1 == 0 {
+            return Err(RngError::InvalidSeed("the PCG increment has to be odd"));
+        }
+        Ok(Self { state, inc })
+    }
+}
+
+impl From<PcgInnerStateSetseq128> for [u128; 2] {
+    #[inline]
+    fn from(pcg: PcgInnerStateSetseq128) -> Self {
+        pcg.parts()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for PcgInnerStateSetseq128 {
+    /// Serializes as `[state, inc]`, without going through `Clone`.
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&self.parts(), serializer)
+    }
+}
+
+/// steps the selected stream and outputs with `rxs_m_xs`, like [`Pcg128`] does on its single stream
+impl RngCore for PcgInnerStateSetseq128 {
+    /// Fill `dest` with random data.
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        lineage::stepped(self);
+        polyfill_fill_bytes_u128(Self::setseq_rxs_m_xs)(self, dest);
+    }
+
+    /// Return the next random `u32`.
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        lineage::stepped(self);
+        self.setseq_xsh_rs() as u32
+    }
+
+    /// Return the next random `u64`.
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        lineage::stepped(self);
+        self.setseq_xsh_rs()
+    }
+}
+
+impl BranchRng<Self> for PcgInnerStateSetseq128 {
+    /// Draws the state and the stream of the branch from `self`, picking a stream other than `self`'s.
+    #[inline]
+    fn branch_rng(&mut self) -> Self {
+        let initstate = u128::random(self);
+        let mut initseq = u128::random(self);
+        if (initseq << 1) | 1 == self.inc {
+            initseq = initseq.wrapping_add(1);
+        }
+        Self::setseq_seeded(initstate, initseq)
+    }
+}
+
 const PCG128_DEFAULT_MUL: u128 = pcg128_const(2549297995355413924, 4865540595714422341);
 const PCG128_DEFAULT_INC: u128 = pcg128_const(6364136223846793005, 1442695040888963407);
 const PCG128_ONESEQ_INIT: u128 = pcg128_const(0xb8dc10e158a92392, 0x98046df007ec0a53);
@@ -382,6 +506,13 @@ impl PcgInnerState128 {
 }
 
 impl PcgInnerStateSetseq128 {
+    /// the state as `[state, inc]`
+    #[inline]
+    #[must_use]
+    pub(crate) const fn parts(&self) -> [u128; 2] {
+        [self.state, self.inc]
+    }
+
     #[inline]
     #[must_use]
     pub const fn zeroed() -> Self {
@@ -9,24 +9,34 @@
 use core::array;
 use core::ptr;
 
-use rand_core::RngCore;
+use rand_core::{RngCore, SeedableRng};
 
 use crate::branch_rng::BranchRng;
+use crate::lineage::{self, impl_tracked_clone};
 use crate::polyfill_next_f32_next_f64_from_fn;
+use crate::sample::Random;
+use crate::RngError;
 
-use super::polyfill::polyfill_fill_bytes_u16;
+use super::{polyfill::polyfill_fill_bytes_u16, SplitMix64};
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct Pcg16 {
     state: PcgInnerState16,
 }
 
+impl_tracked_clone! {
+    Pcg16 { state };
+}
+
 impl BranchRng<Self> for Pcg16 {
+    /// There is a single stream, so the branch is seeded with a draw from `self` instead.
     #[inline]
     fn branch_rng(&mut self) -> Self {
-        let mut oldstate = self.state.clone();
-        PcgInnerState16::oneseq_advance(&mut oldstate, 1);
-        Self { state: oldstate }
+        let initstate = u16::random(self);
+        Self {
+            state: PcgInnerState16::oneseq_seeded(initstate),
+        }
     }
 }
 
@@ -43,27 +53,64 @@ impl RngCore for Pcg16 {
     /// Fill `dest` with random data.
     #[inline]
     fn fill_bytes(&mut self, dest: &mut [u8]) {
+        lineage::stepped(self);
         polyfill_fill_bytes_u16(Self::default_advance)(self, dest);
     }
 
     /// Return the next random `u32`.
     #[inline]
     fn next_u32(&mut self) -> u32 {
+        lineage::stepped(self);
         let mut buf: [u8; 4] = [0; 4];
         <Self as RngCore>::fill_bytes(self, &mut buf);
-        u32::from_ne_bytes(buf)
+        u32::from_le_bytes(buf)
     }
 
     /// Return the next random `u64`.
     #[inline]
     fn next_u64(&mut self) -> u64 {
+        lineage::stepped(self);
         let mut buf: [u8; 8] = [0; 8];
         <Self as RngCore>::fill_bytes(self, &mut buf);
-        u64::from_ne_bytes(buf)
+        u64::from_le_bytes(buf)
+    }
+}
+
+impl SeedableRng for Pcg16 {
+    type Seed = [u8; 2];
+
+    /// Create a new `Pcg16` from the little-endian bytes of its initial state.
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self {
+            state: PcgInnerState16::oneseq_seeded(u16::from_le_bytes(seed)),
+        }
+    }
+
+    /// Create a new `Pcg16` using a `SplitMix64` RNG to expand `state` into a seed.
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_rng(&mut SplitMix64::wrap(state))
     }
 }
 
 impl Pcg16 {
+    /// the raw LCG state
+    #[inline]
+    #[must_use]
+    pub(crate) const fn state_word(&self) -> u16 {
+        self.state.state
+    }
+
+    /// wrap a raw LCG state, as returned by `state_word`
+    #[inline]
+    #[must_use]
+    pub(crate) const fn from_state_word(state: u16) -> Self {
+        Self {
+            state: PcgInnerState16 { state },
+        }
+    }
+
     #[inline]
     #[must_use]
     pub fn new(seed: u16) -> Self {
@@ -87,16 +134,97 @@ impl Pcg16 {
 }
 
 #[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct PcgInnerState16 {
     state: u16,
 }
 
-#[derive(Clone, PartialEq, Eq)]
+/// serializes as `[state, inc]`, the increment has to be odd
+#[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(try_from = "[u16; 2]"))]
 pub struct PcgInnerStateSetseq16 {
     state: u16,
     inc: u16,
 }
 
+impl_tracked_clone! {
+    PcgInnerStateSetseq16 { state, inc };
+}
+
+impl TryFrom<[u16; 2]> for PcgInnerStateSetseq16 {
+    type Error = RngError;
+
+    /// Create the state from `[state, inc]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RngError::InvalidSeed`] if `inc` is even.
+    #[inline]
+    fn try_from([state, inc]: [u16; 2]) -> Result<Self, Self::Error> {
+        if inc & 1 == 0 {
+            return Err(RngError::InvalidSeed("the PCG increment has to be odd"));
+        }
+        Ok(Self { state, inc })
+    }
+}
+
+impl From<PcgInnerStateSetseq16> for [u16; 2] {
+    #[inline]
+    fn from(pcg: PcgInnerStateSetseq16) -> Self {
+        pcg.parts()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for PcgInnerStateSetseq16 {
+    /// Serializes as `[state, inc]`, without going through `Clone`.
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&self.parts(), serializer)
+    }
+}
+
+/// steps the selected stream and outputs with `rxs_m_xs`, like [`Pcg16`] does on its single stream
+impl RngCore for PcgInnerStateSetseq16 {
+    /// Fill `dest` with random data.
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        lineage::stepped(self);
+        polyfill_fill_bytes_u16(Self::setseq_rxs_m_xs)(self, dest);
+    }
+
+    /// Return the next random `u32`.
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        lineage::stepped(self);
+        let mut buf: [u8; 4] = [0; 4];
+        <Self as RngCore>::fill_bytes(self, &mut buf);
+        u32::from_le_bytes(buf)
+    }
+
+    /// Return the next random `u64`.
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        lineage::stepped(self);
+        let mut buf: [u8; 8] = [0; 8];
+        <Self as RngCore>::fill_bytes(self, &mut buf);
+        u64::from_le_bytes(buf)
+    }
+}
+
+impl BranchRng<Self> for PcgInnerStateSetseq16 {
+    /// Draws the state and the stream of the branch from `self`, picking a stream other than `self`'s.
+    #[inline]
+    fn branch_rng(&mut self) -> Self {
+        let initstate = u16::random(self);
+        let mut initseq = u16::random(self);
+        if (initseq << 1) | 1 == self.inc {
+            initseq = initseq.wrapping_add(1);
+        }
+        Self::setseq_seeded(initstate, initseq)
+    }
+}
+
 const PCG16_DEFAULT_MULT: u16 = 12829;
 const PCG16_DEFAULT_INC: u16 = 47989;
 const PCG16_ONESEQ_INIT: u16 = 0x20df;
@@ -316,6 +444,13 @@ impl PcgInnerState16 {
 }
 
 impl PcgInnerStateSetseq16 {
+    /// the state as `[state, inc]`
+    #[inline]
+    #[must_use]
+    pub(crate) const fn parts(&self) -> [u16; 2] {
+        [self.state, self.inc]
+    }
+
     #[inline]
     #[must_use]
     pub const fn zeroed() -> Self {
@@ -8,17 +8,24 @@
 
 use core::ptr;
 
-use rand_core::RngCore;
+use rand_core::{RngCore, SeedableRng};
 
-use crate::{branch_rng::BranchRng, polyfill_next_f32_next_f64_from_fn};
+use crate::lineage::{self, impl_tracked_clone};
+use crate::sample::Random;
+use crate::{branch_rng::BranchRng, polyfill_next_f32_next_f64_from_fn, RngError};
 
-use super::polyfill::polyfill_fill_bytes_u32;
+use super::{polyfill::polyfill_fill_bytes_u32, SplitMix64};
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct Pcg32 {
     state: PcgInnerState32,
 }
 
+impl_tracked_clone! {
+    Pcg32 { state };
+}
+
 impl Default for Pcg32 {
     #[inline]
     fn default() -> Self {
@@ -29,6 +36,22 @@ impl Default for Pcg32 {
 }
 
 impl Pcg32 {
+    /// the raw LCG state
+    #[inline]
+    #[must_use]
+    pub(crate) const fn state_word(&self) -> u32 {
+        self.state.state
+    }
+
+    /// wrap a raw LCG state, as returned by `state_word`
+    #[inline]
+    #[must_use]
+    pub(crate) const fn from_state_word(state: u32) -> Self {
+        Self {
+            state: PcgInnerState32 { state },
+        }
+    }
+
     #[inline]
     #[must_use]
     #[must_use]
@@ -55,37 +78,137 @@ impl Pcg32 {
 impl RngCore for Pcg32 {
     #[inline]
     fn fill_bytes(&mut self, dest: &mut [u8]) {
+        lineage::stepped(self);
         polyfill_fill_bytes_u32(Self::default_advance)(self, dest);
     }
 
     #[inline]
     fn next_u32(&mut self) -> u32 {
+        lineage::stepped(self);
         self.default_advance()
     }
 
     #[inline]
     fn next_u64(&mut self) -> u64 {
+        lineage::stepped(self);
         (u64::from(self.default_advance()) << 32) | u64::from(self.default_advance())
     }
 }
 
+impl SeedableRng for Pcg32 {
+    type Seed = [u8; 4];
+
+    /// Create a new `Pcg32` from the little-endian bytes of its initial state.
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self {
+            state: PcgInnerState32::oneseq_seeded(u32::from_le_bytes(seed)),
+        }
+    }
+
+    /// Create a new `Pcg32` using a `SplitMix64` RNG to expand `state` into a seed.
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_rng(&mut SplitMix64::wrap(state))
+    }
+}
+
 #[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct PcgInnerState32 {
     state: u32,
 }
 
-#[derive(Clone, PartialEq, Eq)]
+/// serializes as `[state, inc]`, the increment has to be odd
+#[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(try_from = "[u32; 2]"))]
 pub struct PcgInnerStateSetseq32 {
     state: u32,
     inc: u32,
 }
 
+impl_tracked_clone! {
+    PcgInnerStateSetseq32 { state, inc };
+}
+
+impl TryFrom<[u32; 2]> for PcgInnerStateSetseq32 {
+    type Error = RngError;
+
+    /// Create the state from `[state, inc]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RngError::InvalidSeed`] if `inc` is even.
+    #[inline]
+    fn try_from([state, inc]: [u32; 2]) -> Result<Self, Self::Error> {
+        if inc & 1 == 0 {
+            return Err(RngError::InvalidSeed("the PCG increment has to be odd"));
+        }
+        Ok(Self { state, inc })
+    }
+}
+
+impl From<PcgInnerStateSetseq32> for [u32; 2] {
+    #[inline]
+    fn from(pcg: PcgInnerStateSetseq32) -> Self {
+        pcg.parts()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for PcgInnerStateSetseq32 {
+    /// Serializes as `[state, inc]`, without going through `Clone`.
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&self.parts(), serializer)
+    }
+}
+
+/// steps the selected stream and outputs with `rxs_m_xs`, like [`Pcg32`] does on its single stream
+impl RngCore for PcgInnerStateSetseq32 {
+    /// Fill `dest` with random data.
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        lineage::stepped(self);
+        polyfill_fill_bytes_u32(Self::setseq_rxs_m_xs)(self, dest);
+    }
+
+    /// Return the next random `u32`.
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        lineage::stepped(self);
+        self.setseq_rxs_m_xs()
+    }
+
+    /// Return the next random `u64`.
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        lineage::stepped(self);
+        (u64::from(self.setseq_rxs_m_xs()) << 32) | u64::from(self.setseq_rxs_m_xs())
+    }
+}
+
+impl BranchRng<Self> for PcgInnerStateSetseq32 {
+    /// Draws the state and the stream of the branch from `self`, picking a stream other than `self`'s.
+    #[inline]
+    fn branch_rng(&mut self) -> Self {
+        let initstate = u32::random(self);
+        let mut initseq = u32::random(self);
+        if (initseq << 1) | 1 == self.inc {
+            initseq = initseq.wrapping_add(1);
+        }
+        Self::setseq_seeded(initstate, initseq)
+    }
+}
+
 impl BranchRng<Self> for Pcg32 {
+    /// There is a single stream, so the branch is seeded with a draw from `self` instead.
     #[inline]
     fn branch_rng(&mut self) -> Self {
-        let mut oldstate = self.state.clone();
-        PcgInnerState32::oneseq_advance(&mut oldstate, 1);
-        Self { state: oldstate }
+        let initstate = u32::random(self);
+        Self {
+            state: PcgInnerState32::oneseq_seeded(initstate),
+        }
     }
 }
 
@@ -307,6 +430,13 @@ impl PcgInnerState32 {
 }
 
 impl PcgInnerStateSetseq32 {
+    /// the state as `[state, inc]`
+    #[inline]
+    #[must_use]
+    pub(crate) const fn parts(&self) -> [u32; 2] {
+        [self.state, self.inc]
+    }
+
     #[inline]
     #[must_use]
     pub const fn zeroed() -> Self {
@@ -8,17 +8,25 @@
 
 use core::ptr;
 
-use rand_core::RngCore;
+use rand_core::{RngCore, SeedableRng};
 
 use crate::branch_rng::BranchRng;
+use crate::lineage::{self, impl_tracked_clone};
+use crate::sample::Random;
+use crate::RngError;
 
-use super::polyfill::polyfill_fill_bytes_u64;
+use super::{polyfill::polyfill_fill_bytes_u64, SplitMix64};
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct Pcg64 {
     state: PcgInnerState64,
 }
 
+impl_tracked_clone! {
+    Pcg64 { state };
+}
+
 impl Default for Pcg64 {
     #[inline]
     fn default() -> Self {
@@ -29,15 +37,33 @@ impl Default for Pcg64 {
 }
 
 impl BranchRng<Self> for Pcg64 {
+    /// There is a single stream, so the branch is seeded with a draw from `self` instead.
     #[inline]
     fn branch_rng(&mut self) -> Self {
-        let mut oldstate = self.state.clone();
-        PcgInnerState64::oneseq_advance(&mut oldstate, 1);
-        Self { state: oldstate }
+        let initstate = u64::random(self);
+        Self {
+            state: PcgInnerState64::oneseq_seeded(initstate),
+        }
     }
 }
 
 impl Pcg64 {
+    /// the raw LCG state
+    #[inline]
+    #[must_use]
+    pub(crate) const fn state_word(&self) -> u64 {
+        self.state.state
+    }
+
+    /// wrap a raw LCG state, as returned by `state_word`
+    #[inline]
+    #[must_use]
+    pub(crate) const fn from_state_word(state: u64) -> Self {
+        Self {
+            state: PcgInnerState64 { state },
+        }
+    }
+
     #[inline]
     #[must_use]
     pub fn new(seed: u64) -> Self {
@@ -59,11 +85,13 @@ impl Pcg64 {
 impl RngCore for Pcg64 {
     #[inline]
     fn fill_bytes(&mut self, dest: &mut [u8]) {
+        lineage::stepped(self);
         polyfill_fill_bytes_u64(Self::default_advance)(self, dest);
     }
 
     #[inline]
     fn next_u32(&mut self) -> u32 {
+        lineage::stepped(self);
         // // TODO: use output functions that dont use the entire inner state
         // self.default_advance() as u32
         self.default_small_advance()
@@ -71,21 +99,117 @@ impl RngCore for Pcg64 {
 
     #[inline]
     fn next_u64(&mut self) -> u64 {
+        lineage::stepped(self);
         self.default_advance()
     }
 }
 
+impl SeedableRng for Pcg64 {
+    type Seed = [u8; 8];
+
+    /// Create a new `Pcg64` from the little-endian bytes of its initial state.
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self {
+            state: PcgInnerState64::oneseq_seeded(u64::from_le_bytes(seed)),
+        }
+    }
+
+    /// Create a new `Pcg64` using a `SplitMix64` RNG to expand `state` into a seed.
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_rng(&mut SplitMix64::wrap(state))
+    }
+}
+
 #[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct PcgInnerState64 {
     state: u64,
 }
 
-#[derive(Clone, PartialEq, Eq)]
+/// serializes as `[state, inc]`, the increment has to be odd
+#[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(try_from = "[u64; 2]"))]
 pub struct PcgInnerStateSetseq64 {
     state: u64,
     inc: u64,
 }
 
+impl_tracked_clone! {
+    PcgInnerStateSetseq64 { state, inc };
+}
+
+impl TryFrom<[u64; 2]> for PcgInnerStateSetseq64 {
+    type Error = RngError;
+
+    /// Create the state from `[state, inc]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RngError::InvalidSeed`] if `inc` is even.
+    #[inline]
+    fn try_from([state, inc]: [u64; 2]) -> Result<Self, Self::Error> {
+        if inc & 1 == 0 {
+            return Err(RngError::InvalidSeed("the PCG increment has to be odd"));
+        }
+        Ok(Self { state, inc })
+    }
+}
+
+impl From<PcgInnerStateSetseq64> for [u64; 2] {
+    #[inline]
+    fn from(pcg: PcgInnerStateSetseq64) -> Self {
+        pcg.parts()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for PcgInnerStateSetseq64 {
+    /// Serializes as `[state, inc]`, without going through `Clone`.
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&self.parts(), serializer)
+    }
+}
+
+/// steps the selected stream and outputs with `rxs_m_xs`, like [`Pcg64`] does on its single stream
+impl RngCore for PcgInnerStateSetseq64 {
+    /// Fill `dest` with random data.
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        lineage::stepped(self);
+        polyfill_fill_bytes_u64(Self::setseq_rxs_m_xs)(self, dest);
+    }
+
+    /// Return the next random `u32`.
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        lineage::stepped(self);
+        self.setseq_xsh_rs()
+    }
+
+    /// Return the next random `u64`.
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        lineage::stepped(self);
+        self.setseq_rxs_m_xs()
+    }
+}
+
+impl BranchRng<Self> for PcgInnerStateSetseq64 {
+    /// Draws the state and the stream of the branch from `self`, picking a stream other than `self`'s.
+    #[inline]
+    fn branch_rng(&mut self) -> Self {
+        let initstate = u64::random(self);
+        let mut initseq = u64::random(self);
+        if (initseq << 1) | 1 == self.inc {
+            initseq = initseq.wrapping_add(1);
+        }
+        Self::setseq_seeded(initstate, initseq)
+    }
+}
+
 const PCG64_DEFAULT_MULT: u64 = 6364136223846793005;
 const PCG64_DEFAULT_INC: u64 = 1442695040888963407;
 const PCG64_ONESEQ_INIT: u64 = 0x4d595df4d0f33173;
@@ -394,6 +518,13 @@ impl PcgInnerState64 {
 }
 
 impl PcgInnerStateSetseq64 {
+    /// the state as `[state, inc]`
+    #[inline]
+    #[must_use]
+    pub(crate) const fn parts(&self) -> [u64; 2] {
+        [self.state, self.inc]
+    }
+
     #[inline]
     #[must_use]
     pub const fn zeroed() -> Self {
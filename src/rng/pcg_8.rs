@@ -8,17 +8,24 @@
 
 use core::ptr;
 
-use rand_core::RngCore;
+use rand_core::{RngCore, SeedableRng};
 
-use crate::{branch_rng::BranchRng, polyfill_next_f32_next_f64_from_fn};
+use crate::lineage::{self, impl_tracked_clone};
+use crate::sample::Random;
+use crate::{branch_rng::BranchRng, polyfill_next_f32_next_f64_from_fn, RngError};
 
-use super::polyfill::polyfill_fill_bytes_u8;
+use super::{polyfill::polyfill_fill_bytes_u8, SplitMix64};
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct Pcg8 {
     state: PcgInnerState8,
 }
 
+impl_tracked_clone! {
+    Pcg8 { state };
+}
+
 impl Default for Pcg8 {
     #[inline]
     fn default() -> Self {
@@ -29,15 +36,33 @@ impl Default for Pcg8 {
 }
 
 impl BranchRng<Self> for Pcg8 {
+    /// There is a single stream, so the branch is seeded with a draw from `self` instead.
     #[inline]
     fn branch_rng(&mut self) -> Self {
-        let mut oldstate = self.state.clone();
-        PcgInnerState8::oneseq_advance(&mut oldstate, 1);
-        Self { state: oldstate }
+        let initstate = u8::random(self);
+        Self {
+            state: PcgInnerState8::oneseq_seeded(initstate),
+        }
     }
 }
 
 impl Pcg8 {
+    /// the raw LCG state
+    #[inline]
+    #[must_use]
+    pub(crate) const fn state_word(&self) -> u8 {
+        self.state.state
+    }
+
+    /// wrap a raw LCG state, as returned by `state_word`
+    #[inline]
+    #[must_use]
+    pub(crate) const fn from_state_word(state: u8) -> Self {
+        Self {
+            state: PcgInnerState8 { state },
+        }
+    }
+
     #[inline]
     #[must_use]
     pub fn new(seed: u8) -> Self {
@@ -59,37 +84,139 @@ impl RngCore for Pcg8 {
     /// Fill `dest` with random data.
     #[inline]
     fn fill_bytes(&mut self, dest: &mut [u8]) {
+        lineage::stepped(self);
         polyfill_fill_bytes_u8(Self::default_advance)(self, dest);
     }
 
     /// Return the next random `u32`.
     #[inline]
     fn next_u32(&mut self) -> u32 {
+        lineage::stepped(self);
         let mut buf: [u8; 4] = [0; 4];
         <Self as RngCore>::fill_bytes(self, &mut buf);
-        u32::from_ne_bytes(buf)
+        u32::from_le_bytes(buf)
     }
 
     /// Return the next random `u64`.
     #[inline]
     fn next_u64(&mut self) -> u64 {
+        lineage::stepped(self);
         let mut buf: [u8; 8] = [0; 8];
         <Self as RngCore>::fill_bytes(self, &mut buf);
-        u64::from_ne_bytes(buf)
+        u64::from_le_bytes(buf)
+    }
+}
+
+impl SeedableRng for Pcg8 {
+    type Seed = [u8; 1];
+
+    /// Create a new `Pcg8` from the little-endian bytes of its initial state.
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self {
+            state: PcgInnerState8::oneseq_seeded(u8::from_le_bytes(seed)),
+        }
+    }
+
+    /// Create a new `Pcg8` using a `SplitMix64` RNG to expand `state` into a seed.
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_rng(&mut SplitMix64::wrap(state))
     }
 }
 
 #[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct PcgInnerState8 {
     state: u8,
 }
 
-#[derive(Clone, PartialEq, Eq)]
+/// serializes as `[state, inc]`, the increment has to be odd
+#[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(try_from = "[u8; 2]"))]
 pub struct PcgInnerStateSetseq8 {
     state: u8,
     inc: u8,
 }
 
+impl_tracked_clone! {
+    PcgInnerStateSetseq8 { state, inc };
+}
+
+impl TryFrom<[u8; 2]> for PcgInnerStateSetseq8 {
+    type Error = RngError;
+
+    /// Create the state from `[state, inc]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RngError::InvalidSeed`] if `inc` is even.
+    #[inline]
+    fn try_from([state, inc]: [u8; 2]) -> Result<Self, Self::Error> {
+        if inc & 1 == 0 {
+            return Err(RngError::InvalidSeed("the PCG increment has to be odd"));
+        }
+        Ok(Self { state, inc })
+    }
+}
+
+impl From<PcgInnerStateSetseq8> for [u8; 2] {
+    #[inline]
+    fn from(pcg: PcgInnerStateSetseq8) -> Self {
+        pcg.parts()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for PcgInnerStateSetseq8 {
+    /// Serializes as `[state, inc]`, without going through `Clone`.
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&self.parts(), serializer)
+    }
+}
+
+/// steps the selected stream and outputs with `rxs_m_xs`, like [`Pcg8`] does on its single stream
+impl RngCore for PcgInnerStateSetseq8 {
+    /// Fill `dest` with random data.
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        lineage::stepped(self);
+        polyfill_fill_bytes_u8(Self::setseq_rxs_m_xs)(self, dest);
+    }
+
+    /// Return the next random `u32`.
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        lineage::stepped(self);
+        let mut buf: [u8; 4] = [0; 4];
+        <Self as RngCore>::fill_bytes(self, &mut buf);
+        u32::from_le_bytes(buf)
+    }
+
+    /// Return the next random `u64`.
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        lineage::stepped(self);
+        let mut buf: [u8; 8] = [0; 8];
+        <Self as RngCore>::fill_bytes(self, &mut buf);
+        u64::from_le_bytes(buf)
+    }
+}
+
+impl BranchRng<Self> for PcgInnerStateSetseq8 {
+    /// Draws the state and the stream of the branch from `self`, picking a stream other than `self`'s.
+    #[inline]
+    fn branch_rng(&mut self) -> Self {
+        let initstate = u8::random(self);
+        let mut initseq = u8::random(self);
+        if (initseq << 1) | 1 == self.inc {
+            initseq = initseq.wrapping_add(1);
+        }
+        Self::setseq_seeded(initstate, initseq)
+    }
+}
+
 const PCG8_DEFAULT_MULT: u8 = 141;
 const PCG8_DEFAULT_INC: u8 = 77;
 const PCG8_ONESEQ_INIT: u8 = 0xd7;
@@ -200,6 +327,13 @@ impl PcgInnerState8 {
 }
 
 impl PcgInnerStateSetseq8 {
+    /// the state as `[state, inc]`
+    #[inline]
+    #[must_use]
+    pub(crate) const fn parts(&self) -> [u8; 2] {
+        [self.state, self.inc]
+    }
+
     #[inline]
     #[must_use]
     pub const fn zeroed() -> Self {
@@ -2,7 +2,8 @@ macro_rules! generate_polyfill_fill_bytes {
     ($fn_name:ident, $ty:ty) => {
         #[doc = "A polyfill function that uses the `next_num_fn` to generate a value of"]
         #[doc = ::core::concat!("type ", ::core::stringify!($ty), " and uses iterator")]
-        #[doc = "methods to fill the destination bytes. Use this with the widest number"]
+        #[doc = "methods to fill the destination bytes in little-endian byte order, so the"]
+        #[doc = "output is the same on every host. Use this with the widest number"]
         #[doc = "generation method of your RNG to implement the optimal `next_<primitive>`"]
         #[doc = "methods automatically for your RNG."]
         #[inline]
@@ -20,14 +21,14 @@ macro_rules! generate_polyfill_fill_bytes {
 
                 for slice in byte_iterator.by_ref() {
                     let next: $ty = next_num_fn(this);
-                    let next_bytes: [u8; SIZE_BYTES] = next.to_ne_bytes();
+                    let next_bytes: [u8; SIZE_BYTES] = next.to_le_bytes();
                     slice.copy_from_slice(&next_bytes[..]);
                 }
 
                 let remainder = byte_iterator.into_remainder();
                 if !remainder.is_empty() {
                     let next: $ty = next_num_fn(this);
-                    let next_bytes: [u8; SIZE_BYTES] = next.to_ne_bytes();
+                    let next_bytes: [u8; SIZE_BYTES] = next.to_le_bytes();
                     remainder.copy_from_slice(&next_bytes[..remainder.len()]);
                 }
             }
@@ -1,17 +1,30 @@
 use super::polyfill::polyfill_fill_bytes_u64;
-use crate::{genrandom::GenRandom, polyfill_next_f32_next_f64_from_fn};
-use rand_core::RngCore;
+use crate::lineage::{self, impl_tracked_clone};
+use crate::{entropy, polyfill_next_f32_next_f64_from_fn};
+use rand_core::{RngCore, SeedableRng};
 
 /// an RNG engine used for seeding other RNGs
-#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[derive(Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct SplitMix64 {
     x: u64,
 }
 
+impl_tracked_clone! {
+    SplitMix64 { x };
+}
+
 impl Default for SplitMix64 {
+    /// Seeds the RNG from the [`GlobalEntropy`](crate::entropy::GlobalEntropy) source, see [`EntropyFallback`](crate::entropy::EntropyFallback) for what happens when it isn't available.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if the entropy source fails to generate a seed and the fallback policy is
+    /// [`EntropyFallback::Panic`](crate::entropy::EntropyFallback::Panic).
     #[inline]
+    #[track_caller]
     fn default() -> Self {
-        let seed = u64::get_random().unwrap();
+        let seed = entropy::default_seed();
         Self::wrap(seed)
     }
 }
@@ -29,22 +42,43 @@ impl RngCore for SplitMix64 {
     /// Fill `dest` with random data.
     #[inline]
     fn fill_bytes(&mut self, dest: &mut [u8]) {
+        lineage::stepped(self);
         polyfill_fill_bytes_u64(Self::mix)(self, dest);
     }
 
     /// Return the next random `u32`.
     #[inline]
     fn next_u32(&mut self) -> u32 {
+        lineage::stepped(self);
         self.next_u64() as u32
     }
 
     /// Return the next random `u64`.
     #[inline]
     fn next_u64(&mut self) -> u64 {
+        lineage::stepped(self);
         self.mix()
     }
 }
 
+impl SeedableRng for SplitMix64 {
+    type Seed = [u8; 8];
+
+    /// Create a new `SplitMix64` from the little-endian bytes of its initial state.
+    ///
+    /// Every seed is valid, including zero.
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::wrap(u64::from_le_bytes(seed))
+    }
+
+    /// Create a new `SplitMix64` with `state` as its initial state, same as [`SplitMix64::new`].
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::new(state)
+    }
+}
+
 impl SplitMix64 {
     /// seed the RNG
     ///
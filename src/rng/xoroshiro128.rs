@@ -1,26 +1,60 @@
-use rand_core::RngCore;
+use rand_core::{RngCore, SeedableRng};
 
-use crate::{genrandom::GenRandom, polyfill_next_f32_next_f64_from_fn};
+use crate::lineage::{self, impl_tracked_clone};
+use crate::{entropy, polyfill_next_f32_next_f64_from_fn, RngError};
 
 use super::{polyfill::polyfill_fill_bytes_u64, SplitMix64};
 
 /// bigger cheap and dirty random numbers
 ///
 /// this is the xorshiro128+ implementation
-#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[derive(Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(try_from = "[u64; 2]"))]
 pub struct XoRoShiRo128 {
     state: [u64; 2],
 }
 
+impl_tracked_clone! {
+    XoRoShiRo128 { state };
+}
+
+impl TryFrom<[u64; 2]> for XoRoShiRo128 {
+    type Error = RngError;
+
+    /// Same as [`XoRoShiRo128::try_wrap`].
+    #[inline]
+    fn try_from(state: [u64; 2]) -> Result<Self, Self::Error> {
+        Self::try_wrap(state)
+    }
+}
+
+impl From<XoRoShiRo128> for [u64; 2] {
+    #[inline]
+    fn from(rng: XoRoShiRo128) -> Self {
+        rng.get_current_state()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for XoRoShiRo128 {
+    /// Serializes as `[u64; 2]`, without going through `Clone`.
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&self.get_current_state(), serializer)
+    }
+}
+
 impl Default for XoRoShiRo128 {
+    /// Fills the whole 128-bit state from the [`GlobalEntropy`](crate::entropy::GlobalEntropy) source, see [`EntropyFallback`](crate::entropy::EntropyFallback) for what happens when it isn't available.
+    ///
     /// # Panics
     ///
-    /// This will panic if the OS RNG fails to generate a seed
+    /// This will panic if the entropy source fails to generate a seed and the fallback policy is
+    /// [`EntropyFallback::Panic`](crate::entropy::EntropyFallback::Panic).
     #[inline]
     #[track_caller]
     fn default() -> Self {
-        let seed = [u64::get_random().unwrap(), u64::get_random().unwrap()];
-        Self::wrap(seed)
+        entropy::default_rng()
     }
 }
 
@@ -28,22 +62,49 @@ impl RngCore for XoRoShiRo128 {
     /// Fill `dest` with random data.
     #[inline]
     fn fill_bytes(&mut self, dest: &mut [u8]) {
+        lineage::stepped(self);
         polyfill_fill_bytes_u64(Self::next_u64)(self, dest);
     }
 
     /// Return the next random `u32`.
     #[inline]
     fn next_u32(&mut self) -> u32 {
+        lineage::stepped(self);
         self.next_u64() as u32
     }
 
     /// Return the next random `u64`.
     #[inline]
     fn next_u64(&mut self) -> u64 {
+        lineage::stepped(self);
         self.advance()
     }
 }
 
+impl SeedableRng for XoRoShiRo128 {
+    type Seed = [u8; 16];
+
+    /// Create a new `XoRoShiRo128` from the little-endian bytes of its two initial state words.
+    ///
+    /// The all-zero seed is a fixed point of the generator, so it is remapped to the
+    /// state of `XoRoShiRo128::new(0)` instead of panicking like [`XoRoShiRo128::wrap`] does.
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        match u128::from_le_bytes(seed) {
+            0 => Self::new(0),
+            x => Self {
+                state: [x as u64, (x >> 64) as u64],
+            },
+        }
+    }
+
+    /// Create a new `XoRoShiRo128` using a `SplitMix64` RNG to expand `state` into a seed.
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_rng(&mut SplitMix64::wrap(state))
+    }
+}
+
 impl XoRoShiRo128 {
     const SHORT_JUMP_TABLE: [u64; 2] = [0xdf90_0294_d8f5_54a5, 0x1708_65df_4b32_01fc];
     const LONG_JUMP_TABLE: [u64; 2] = [0xd2a9_8b26_625e_ee7b, 0xdddf_9b10_90aa_7ac1];
@@ -53,6 +114,7 @@ impl XoRoShiRo128 {
     #[must_use]
     pub const fn new(seed: u64) -> Self {
         let mut smx = SplitMix64::wrap(seed);
+        // two consecutive outputs of `SplitMix64` are never both zero, so `wrap` accepts them
         let seed = [smx.mix(), smx.mix()];
         Self::wrap(seed)
     }
@@ -60,13 +122,31 @@ impl XoRoShiRo128 {
     /// wrap a value directly into the RNG
     ///
     /// recommended to use `seed_using_splitmix` instead as it will handle 0 seeds
+    ///
+    /// # Panics
+    ///
+    /// This will panic if `seed` is all zeros, use [`SeedableRng::from_seed`] to remap it instead.
     #[inline]
     #[must_use]
     pub const fn wrap(seed: [u64; 2]) -> Self {
-        assert!(seed[0] != 0, "XoRoShiRo128 cannot be seeded with 0");
+        assert!(seed[0] != 0 || seed[1] != 0, "XoRoShiRo128 cannot be seeded with 0");
         Self { state: seed }
     }
 
+    /// wrap a value directly into the RNG, without panicking
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RngError::InvalidSeed`] if `seed` is all zeros, the one state the generator
+    /// can't leave.
+    #[inline]
+    pub const fn try_wrap(seed: [u64; 2]) -> Result<Self, RngError> {
+        if seed[0] == 0 && seed[1] == 0 {
+            return Err(RngError::InvalidSeed("XoRoShiRo128 cannot be seeded with 0"));
+        }
+        Ok(Self { state: seed })
+    }
+
     polyfill_next_f32_next_f64_from_fn!(
         pub fn next_f32, next_f64(Self::advance = u64);
     );
@@ -106,6 +186,7 @@ impl XoRoShiRo128 {
                     s0 ^= self.state[0];
                     s1 ^= self.state[1];
                 }
+                self.advance();
                 b = b.wrapping_add(1);
             }
 
@@ -151,6 +232,7 @@ impl XoRoShiRo128 {
                     s0 ^= self.state[0];
                     s1 ^= self.state[1];
                 }
+                self.advance();
                 b = b.wrapping_add(1);
             }
 
@@ -200,3 +282,42 @@ impl Iterator for XoRoShiRo128 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::XoRoShiRo128;
+    use rand_core::{RngCore, SeedableRng};
+
+    #[test]
+    fn from_seed_keeps_the_state() {
+        for state in [[0, 1], [1, 0], [u64::MAX, 7]] {
+            let seed = (u128::from(state[1]) << 64 | u128::from(state[0])).to_le_bytes();
+            assert_eq!(XoRoShiRo128::from_seed(seed).get_current_state(), state);
+        }
+        assert_eq!(XoRoShiRo128::from_seed([0; 16]), XoRoShiRo128::new(0));
+    }
+
+    #[test]
+    fn only_the_all_zero_state_is_rejected() {
+        // every nonzero state is on the generator's single cycle, `[0, 1]` included
+        let mut rng = XoRoShiRo128::wrap([0, 1]);
+        assert_eq!(rng.next_u64(), 1);
+        assert_ne!(rng.get_current_state(), [0, 0]);
+        assert_eq!(XoRoShiRo128::try_wrap([0, 1]).ok(), Some(XoRoShiRo128::wrap([0, 1])));
+        assert!(XoRoShiRo128::try_wrap([0, 0]).is_err());
+        // what serde deserializes through
+        assert!(XoRoShiRo128::try_from([0, 1]).is_ok());
+    }
+
+    #[test]
+    fn jumps_advance_by_2_pow_64_and_2_pow_96() {
+        // the states after 2^64 and 2^96 steps, from powers of the generator's matrix over GF(2)
+        let mut rng = XoRoShiRo128::wrap([0x1234, 0x5678]);
+        rng.jump();
+        assert_eq!(rng.get_current_state(), [0xb32c_7d2e_56fb_8673, 0xe222_fe84_46c0_75fd]);
+
+        let mut rng = XoRoShiRo128::wrap([0x1234, 0x5678]);
+        rng.long_jump();
+        assert_eq!(rng.get_current_state(), [0x02b4_7a17_842c_dde6, 0xeb4e_4526_47c3_b74b]);
+    }
+}
@@ -1,18 +1,53 @@
-use crate::polyfill_next_f32_next_f64_from_fn;
+use crate::lineage::{self, impl_tracked_clone};
+use crate::{polyfill_next_f32_next_f64_from_fn, RngError};
 
 use super::{polyfill::polyfill_fill_bytes_u64, SplitMix64};
-use rand_core::RngCore;
+use rand_core::{RngCore, SeedableRng};
 
 /// cheap and dirty random numbers
-#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[derive(Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(try_from = "[u64; 2]"))]
 pub struct XorShift128 {
     state: [u64; 2],
 }
 
+impl_tracked_clone! {
+    XorShift128 { state };
+}
+
+impl TryFrom<[u64; 2]> for XorShift128 {
+    type Error = RngError;
+
+    /// Same as [`XorShift128::try_wrap`].
+    #[inline]
+    fn try_from(state: [u64; 2]) -> Result<Self, Self::Error> {
+        Self::try_wrap(state)
+    }
+}
+
+impl From<XorShift128> for [u64; 2] {
+    #[inline]
+    fn from(rng: XorShift128) -> Self {
+        rng.get_current_state()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for XorShift128 {
+    /// Serializes as `[u64; 2]`, without going through `Clone`.
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&self.get_current_state(), serializer)
+    }
+}
+
 impl Default for XorShift128 {
+    /// Seeds the RNG from the [`GlobalEntropy`](crate::entropy::GlobalEntropy) source, see [`EntropyFallback`](crate::entropy::EntropyFallback) for what happens when it isn't available.
+    ///
     /// # Panics
     ///
-    /// This will panic if the OS RNG fails to generate a seed
+    /// This will panic if the entropy source fails to generate a seed and the fallback policy is
+    /// [`EntropyFallback::Panic`](crate::entropy::EntropyFallback::Panic).
     #[inline]
     #[track_caller]
     fn default() -> Self {
@@ -26,22 +61,47 @@ impl RngCore for XorShift128 {
     /// Fill `dest` with random data.
     #[inline]
     fn fill_bytes(&mut self, dest: &mut [u8]) {
+        lineage::stepped(self);
         polyfill_fill_bytes_u64(Self::next_u64)(self, dest);
     }
 
     /// Return the next random `u32`.
     #[inline]
     fn next_u32(&mut self) -> u32 {
+        lineage::stepped(self);
         self.next_u64() as u32
     }
 
     /// Return the next random `u64`.
     #[inline]
     fn next_u64(&mut self) -> u64 {
+        lineage::stepped(self);
         self.advance()
     }
 }
 
+impl SeedableRng for XorShift128 {
+    type Seed = [u8; 16];
+
+    /// Create a new `XorShift128` from the little-endian bytes of its two initial state words.
+    ///
+    /// The all-zero seed is a fixed point of the generator, so it is remapped to the
+    /// state of `XorShift128::new(0)` instead.
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        match u128::from_le_bytes(seed) {
+            0 => Self::new(0),
+            x => Self::wrap([x as u64, (x >> 64) as u64]),
+        }
+    }
+
+    /// Create a new `XorShift128` using a `SplitMix64` RNG to expand `state` into a seed.
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_rng(&mut SplitMix64::wrap(state))
+    }
+}
+
 impl XorShift128 {
     /// seed the RNG using a `SplitMix64` RNG
     #[inline]
@@ -60,6 +120,19 @@ impl XorShift128 {
         Self { state: seed }
     }
 
+    /// wrap a value directly into the RNG, rejecting the all-zero state it can't leave
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RngError::InvalidSeed`] if both words of `seed` are 0.
+    #[inline]
+    pub const fn try_wrap(seed: [u64; 2]) -> Result<Self, RngError> {
+        if seed[0] == 0 && seed[1] == 0 {
+            return Err(RngError::InvalidSeed("XorShift128 cannot be seeded with 0"));
+        }
+        Ok(Self { state: seed })
+    }
+
     polyfill_next_f32_next_f64_from_fn!(
         pub fn next_f32, next_f64(Self::advance = u64);
     );
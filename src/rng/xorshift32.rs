@@ -1,26 +1,41 @@
 use super::polyfill::polyfill_fill_bytes_u32;
 use super::SplitMix64;
-use crate::polyfill_next_f32_next_f64_from_fn;
-use rand_core::RngCore;
+use crate::lineage::{self, impl_tracked_clone};
+use crate::{entropy, polyfill_next_f32_next_f64_from_fn, RngError};
+use rand_core::{RngCore, SeedableRng};
 
 /// cheap and dirty random numbers
-#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[derive(Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(try_from = "u32"))]
 pub struct XorShift32 {
     x: u32,
 }
 
+impl_tracked_clone! {
+    XorShift32 { x };
+}
+
 impl XorShift32 {
     /// seed the RNG using a `SplitMix64` RNG
     #[inline]
     #[must_use]
     pub const fn new(seed: u64) -> Self {
         let mut smx = SplitMix64::wrap(seed);
-        Self::wrap(smx.mix() as u32)
+        let mut x = smx.mix() as u32;
+        // the low half of a `SplitMix64` output can be 0, which `wrap` doesn't accept
+        while x == 0 {
+            x = smx.mix() as u32;
+        }
+        Self::wrap(x)
     }
 
     /// wrap a value directly into the RNG
     ///
     /// recommended to use `seed_using_splitmix` instead as it will handle 0 seeds
+    ///
+    /// # Panics
+    ///
+    /// This will panic if `seed` is 0, use [`SeedableRng::from_seed`] to remap it instead.
     #[inline]
     #[must_use]
     #[track_caller]
@@ -29,6 +44,19 @@ impl XorShift32 {
         Self { x: seed }
     }
 
+    /// wrap a value directly into the RNG, without panicking
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RngError::InvalidSeed`] if `seed` is 0.
+    #[inline]
+    pub const fn try_wrap(seed: u32) -> Result<Self, RngError> {
+        if seed == 0 {
+            return Err(RngError::InvalidSeed("XorShift32 cannot be seeded with 0"));
+        }
+        Ok(Self { x: seed })
+    }
+
     polyfill_next_f32_next_f64_from_fn!(
         pub fn next_f32, next_f64(Self::advance = u32);
     );
@@ -62,15 +90,43 @@ pub const fn xorshift32_step(mut x: u32) -> u32 {
     x
 }
 
+impl TryFrom<u32> for XorShift32 {
+    type Error = RngError;
+
+    /// Same as [`XorShift32::try_wrap`].
+    #[inline]
+    fn try_from(state: u32) -> Result<Self, Self::Error> {
+        Self::try_wrap(state)
+    }
+}
+
+impl From<XorShift32> for u32 {
+    #[inline]
+    fn from(rng: XorShift32) -> Self {
+        rng.get_current_state()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for XorShift32 {
+    /// Serializes as `u32`, without going through `Clone`.
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&self.get_current_state(), serializer)
+    }
+}
+
 impl Default for XorShift32 {
+    /// Seeds the RNG from the [`GlobalEntropy`](crate::entropy::GlobalEntropy) source, see [`EntropyFallback`](crate::entropy::EntropyFallback) for what happens when it isn't available.
+    ///
     /// # Panics
     ///
-    /// This will panic if the OS RNG fails to generate a seed
+    /// This will panic if the entropy source fails to generate a seed and the fallback policy is
+    /// [`EntropyFallback::Panic`](crate::entropy::EntropyFallback::Panic).
     #[inline]
     #[track_caller]
     fn default() -> Self {
-        let mut seed = SplitMix64::default();
-        Self::wrap(seed.mix() as u32)
+        Self::new(entropy::default_seed())
     }
 }
 
@@ -78,22 +134,47 @@ impl RngCore for XorShift32 {
     /// Fill `dest` with random data.
     #[inline]
     fn fill_bytes(&mut self, dest: &mut [u8]) {
+        lineage::stepped(self);
         polyfill_fill_bytes_u32(Self::next_u32)(self, dest);
     }
 
     /// Return the next random `u32`.
     #[inline]
     fn next_u32(&mut self) -> u32 {
+        lineage::stepped(self);
         self.advance()
     }
 
     /// Return the next random `u64`.
     #[inline]
     fn next_u64(&mut self) -> u64 {
+        lineage::stepped(self);
         ((u64::from(self.advance())) << 32) | u64::from(self.advance())
     }
 }
 
+impl SeedableRng for XorShift32 {
+    type Seed = [u8; 4];
+
+    /// Create a new `XorShift32` from the little-endian bytes of its initial state.
+    ///
+    /// The all-zero seed is a fixed point of the generator, so it is remapped to the
+    /// state of `XorShift32::new(0)` instead of panicking like [`XorShift32::wrap`] does.
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        match u32::from_le_bytes(seed) {
+            0 => Self::new(0),
+            x => Self::wrap(x),
+        }
+    }
+
+    /// Create a new `XorShift32` using a `SplitMix64` RNG to expand `state` into a seed.
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_rng(&mut SplitMix64::wrap(state))
+    }
+}
+
 impl Iterator for XorShift32 {
     type Item = u32;
 
@@ -1,8 +1,48 @@
-use crate::rng::{Pcg128, Pcg16, Pcg32, Pcg64, Pcg8, SplitMix64, XoRoShiRo128, XorShift128, XorShift32};
-use core::mem;
-use rand_core::RngCore;
+use crate::entropy::EntropySource;
+use crate::mock::{BytesRng, ConstRng, SequenceRng, StepRng};
+use crate::recording::{DrawSink, Recording, Replay};
+use crate::reseeding::Reseeding;
+use crate::rng::pcg_advanced::pcg_128::PcgInnerStateSetseq128;
+use crate::rng::pcg_advanced::pcg_16::PcgInnerStateSetseq16;
+use crate::rng::pcg_advanced::pcg_32::PcgInnerStateSetseq32;
+use crate::rng::pcg_advanced::pcg_64::PcgInnerStateSetseq64;
+use crate::rng::pcg_advanced::pcg_8::PcgInnerStateSetseq8;
+use crate::rng::{AnyRng, Pcg128, Pcg16, Pcg32, Pcg64, Pcg8, SplitMix64, XoRoShiRo128, XorShift128, XorShift32};
+use crate::sample;
+use rand_core::{RngCore, SeedableRng};
+
+#[cfg(feature = "std")]
+use crate::testing::ShrinkRng;
+#[cfg(feature = "std")]
+use crate::thread_rng::ThreadRng;
 
 /// Extends `RngCore` to support all primitive integer types.
+///
+/// Values are read from [`RngCore::fill_bytes`] in little-endian byte order, and `usize`/`isize`
+/// are always drawn as 64-bit values, so a seed gives the same values on every target regardless
+/// of its endianness or pointer width.
+///
+/// ```rust
+/// use rand_core::RngCore;
+/// use voxell_rng::prelude::*;
+/// use voxell_rng::rng::SplitMix64;
+///
+/// let word = RngCore::next_u64(&mut SplitMix64::new(0));
+/// assert_eq!(word, 0xe220_a839_7b1d_cdaf);
+///
+/// // the bytes come out little-endian on every host
+/// let mut bytes = [0; 8];
+/// SplitMix64::new(0).fill_bytes(&mut bytes);
+/// assert_eq!(bytes, word.to_le_bytes());
+///
+/// // the extension methods read those bytes as little-endian too
+/// assert_eq!(RngCoreExtension::next_u16(&mut SplitMix64::new(0)), 0xcdaf);
+///
+/// // `usize` takes a whole 64-bit draw, so the stream stays in step on 32-bit targets
+/// let mut rng = SplitMix64::new(0);
+/// assert_eq!(rng.next_usize() as u32, word as u32);
+/// assert_eq!(RngCore::next_u64(&mut rng), 0x6e78_9e6a_a1b9_65f4);
+/// ```
 pub trait RngCoreExtension: RngCore {
     /// next bool element from the rng.
     #[must_use = "please use the generated value"]
@@ -23,6 +63,9 @@ pub trait RngCoreExtension: RngCore {
     #[must_use = "please use the generated value"]
     fn next_u128(&mut self) -> u128;
     /// next usize element from the rng.
+    ///
+    /// Always consumes a 64-bit draw, truncated on 32-bit targets. Use
+    /// [`RngCoreExtension::next_bounded_usize`] for a value in a range that is the same on every target.
     #[must_use = "please use the generated value"]
     fn next_usize(&mut self) -> usize;
 
@@ -41,18 +84,40 @@ pub trait RngCoreExtension: RngCore {
     /// next u128 element from the rng.
     #[must_use = "please use the generated value"]
     fn next_i128(&mut self) -> i128;
-    /// next usize element from the rng.
+    /// next isize element from the rng.
+    ///
+    /// Always consumes a 64-bit draw, truncated on 32-bit targets.
     #[must_use = "please use the generated value"]
     fn next_isize(&mut self) -> isize;
+
+    /// next u64 element from the rng in the range `0..bound`, without modulo bias.
+    ///
+    /// Uses Lemire's multiply-and-reject method, which usually needs a single draw.
+    /// If `bound` is 0, 0 is returned.
+    #[inline]
+    #[must_use = "please use the generated value"]
+    fn next_bounded_u64(&mut self, bound: u64) -> u64 {
+        sample::bounded_u64(self, bound)
+    }
+
+    /// next usize element from the rng in the range `0..bound`, without modulo bias.
+    ///
+    /// Always draws through [`RngCoreExtension::next_bounded_u64`] so the result is the same
+    /// on 32-bit and 64-bit targets. If `bound` is 0, 0 is returned.
+    #[inline]
+    #[must_use = "please use the generated value"]
+    fn next_bounded_usize(&mut self, bound: usize) -> usize {
+        self.next_bounded_u64(bound as u64) as usize
+    }
 }
 
-macro_rules! fill_native_endian_bytes {
+macro_rules! fill_little_endian_bytes {
     ($ty:ty, $bytes:expr, $rng:ident) => {
         #[allow(unused_braces)]
         {
             let mut bytes: [u8; $bytes] = [0; $bytes];
             <Self as RngCore>::fill_bytes($rng, &mut bytes);
-            <$ty>::from_ne_bytes(bytes)
+            <$ty>::from_le_bytes(bytes)
         }
     };
 }
@@ -61,14 +126,14 @@ macro_rules! gen_next_prim_function {
     ($fn_name:ident, $final_type:ty, $byte_width:expr) => {
         #[inline]
         fn $fn_name(&mut self) -> $final_type {
-            fill_native_endian_bytes!($final_type, $byte_width, self)
+            fill_little_endian_bytes!($final_type, $byte_width, self)
         }
     };
 }
 
 macro_rules! extend_rngcore_for {
-    ($ty:ty) => {
-        impl RngCoreExtension for $ty {
+    ([$($generics:tt)*] $ty:ty) => {
+        impl<$($generics)*> RngCoreExtension for $ty {
             #[inline]
             fn next_bool(&mut self) -> bool {
                 self.next_u8() % 2 == 0
@@ -78,15 +143,24 @@ macro_rules! extend_rngcore_for {
             gen_next_prim_function!(next_u32, u32, 4);
             gen_next_prim_function!(next_u64, u64, 8);
             gen_next_prim_function!(next_u128, u128, 16);
-            gen_next_prim_function!(next_usize, usize, { mem::size_of::<usize>() });
+            #[inline]
+            fn next_usize(&mut self) -> usize {
+                RngCoreExtension::next_u64(self) as usize
+            }
             gen_next_prim_function!(next_i8, i8, 1);
             gen_next_prim_function!(next_i16, i16, 2);
             gen_next_prim_function!(next_i32, i32, 4);
             gen_next_prim_function!(next_i64, i64, 8);
             gen_next_prim_function!(next_i128, i128, 16);
-            gen_next_prim_function!(next_isize, isize, { mem::size_of::<isize>() });
+            #[inline]
+            fn next_isize(&mut self) -> isize {
+                self.next_i64() as isize
+            }
         }
     };
+    ($ty:ty) => {
+        extend_rngcore_for!([] $ty);
+    };
 }
 
 extend_rngcore_for!(XorShift128);
@@ -98,3 +172,102 @@ extend_rngcore_for!(Pcg16);
 extend_rngcore_for!(Pcg8);
 extend_rngcore_for!(SplitMix64);
 extend_rngcore_for!(XoRoShiRo128);
+extend_rngcore_for!(PcgInnerStateSetseq128);
+extend_rngcore_for!(PcgInnerStateSetseq64);
+extend_rngcore_for!(PcgInnerStateSetseq32);
+extend_rngcore_for!(PcgInnerStateSetseq16);
+extend_rngcore_for!(PcgInnerStateSetseq8);
+extend_rngcore_for!(AnyRng);
+extend_rngcore_for!([R: RngCore, S: DrawSink] Recording<R, S>);
+extend_rngcore_for!(Replay);
+extend_rngcore_for!(SequenceRng);
+extend_rngcore_for!(ConstRng);
+extend_rngcore_for!(StepRng);
+extend_rngcore_for!(['a] BytesRng<'a>);
+extend_rngcore_for!([R: RngCore + SeedableRng, S: EntropySource] Reseeding<R, S>);
+#[cfg(feature = "std")]
+extend_rngcore_for!(ThreadRng);
+#[cfg(feature = "std")]
+extend_rngcore_for!([R: RngCore] ShrinkRng<R>);
+
+#[cfg(test)]
+mod tests {
+    use super::RngCoreExtension;
+    use crate::rng::SplitMix64;
+    use rand_core::RngCore;
+
+    /// `SplitMix64` as a big-endian host runs it: a word `w` is held in memory as the bytes of
+    /// `w.to_be_bytes()`, which is `w.swap_bytes()` on this host. A polyfill writing the words
+    /// with `to_le_bytes` has to swap those native bytes, while one writing them with
+    /// `to_ne_bytes` copies them as they are.
+    struct BigEndianHost {
+        engine: SplitMix64,
+        native_order_polyfill: bool,
+    }
+
+    impl BigEndianHost {
+        const fn new(native_order_polyfill: bool) -> Self {
+            Self {
+                engine: SplitMix64::new(0),
+                native_order_polyfill,
+            }
+        }
+    }
+
+    impl RngCore for BigEndianHost {
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for chunk in dest.chunks_mut(8) {
+                let native = RngCore::next_u64(self);
+                let bytes = if self.native_order_polyfill {
+                    native.to_ne_bytes()
+                } else {
+                    native.to_be_bytes()
+                };
+                chunk.copy_from_slice(&bytes[..chunk.len()]);
+            }
+        }
+
+        fn next_u32(&mut self) -> u32 {
+            RngCore::next_u64(self) as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            RngCore::next_u64(&mut self.engine).swap_bytes()
+        }
+    }
+
+    extend_rngcore_for!(BigEndianHost);
+
+    /// The draws from `SplitMix64::new(0)`, one word each, and two for the 128-bit integers.
+    ///
+    /// A draw narrower than a word takes its low bytes, and the rest of the word is dropped.
+    fn assert_little_endian_draws<R: RngCoreExtension>(rng: &mut R) {
+        assert!(!rng.next_bool());
+        assert_eq!(rng.next_u8(), 0xf4);
+        assert_eq!(rng.next_u16(), 0x454f);
+        assert_eq!(RngCoreExtension::next_u32(rng), 0x724c_81ec);
+        assert_eq!(RngCoreExtension::next_u64(rng), 0x1b39_896a_51a8_749b);
+        assert_eq!(rng.next_u128(), 0x2c82_9abe_1f45_32e1_53cb_9f0c_747e_a2ea);
+        assert_eq!(rng.next_usize() as u64, 0xc584_133a_c916_ab3c_u64 as usize as u64);
+        assert_eq!(rng.next_i8(), -0x3d);
+        assert_eq!(rng.next_i16(), -0x4f5a);
+        assert_eq!(rng.next_i32(), 0x3cb1_3d09);
+        assert_eq!(rng.next_i64(), -0x3d2c_d91f_faa4_210a);
+        assert_eq!(rng.next_i128(), -0x71e0_8aaa_67c5_56d0_79de_5fc0_1f44_2485);
+        assert_eq!(rng.next_isize() as i64, -0x4ab1_f0e9_ff33_b2e7_i64 as isize as i64);
+    }
+
+    #[test]
+    fn known_values() {
+        assert_little_endian_draws(&mut SplitMix64::new(0));
+    }
+
+    #[test]
+    fn a_big_endian_host_draws_the_same_values() {
+        assert_little_endian_draws(&mut BigEndianHost::new(false));
+
+        // while writing the native bytes would read every word byte-swapped
+        let mut rng = BigEndianHost::new(true);
+        assert_eq!(RngCoreExtension::next_u64(&mut rng), 0xe220_a839_7b1d_cdaf_u64.swap_bytes());
+    }
+}
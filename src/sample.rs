@@ -0,0 +1,576 @@
+// Integers are generated and offset as their unsigned counterparts, the casts between the
+// two are meant to wrap.
+#![allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+
+use core::array;
+use core::ops::{Range, RangeInclusive};
+use rand_core::RngCore;
+
+/// Derive [`Random`] for a struct or an enum, with the `derive` feature.
+///
+/// Fields are generated with their own [`Random`] impl, unless they have one of these attributes:
+///
+/// - `#[random(range = 0..10)]` samples the field from the range with [`SampleRange`].
+/// - `#[random(skip)]` sets the field to its `Default` value.
+///
+/// Enum variants are picked with equal probability, unless they have one of these attributes:
+///
+/// - `#[random(weight = 3)]` makes the variant 3 times as likely as a variant with the default weight of 1.
+/// - `#[random(skip)]` never picks the variant.
+///
+/// ```rust
+/// # #[cfg(feature = "derive")] {
+/// use voxell_rng::rng::XorShift128;
+/// use voxell_rng::sample::Random;
+///
+/// #[derive(Random, Debug)]
+/// enum Biome {
+///     #[random(weight = 3)]
+///     Plains,
+///     Forest { density: f32 },
+///     #[random(skip)]
+///     Void,
+/// }
+///
+/// #[derive(Random, Debug)]
+/// struct Chunk {
+///     #[random(range = -64..=64)]
+///     height: i32,
+///     biome: Biome,
+///     #[random(skip)]
+///     visited: bool,
+/// }
+///
+/// let chunk = Chunk::random(&mut XorShift128::new(7));
+/// assert!((-64..=64).contains(&chunk.height));
+/// assert!(!chunk.visited);
+/// # }
+/// ```
+#[cfg(feature = "derive")]
+pub use voxell_rng_derive::Random;
+
+/// Types that can be generated from any [`RngCore`].
+///
+/// Integers are uniform over their whole range, floats are uniform in `[0, 1)`,
+/// `bool`s are a fair coin flip and `char`s are uniform over the valid scalar values.
+/// Arrays, tuples and `Option`s are built from their parts. `usize` and `isize` always draw 64 bits so the result is
+/// the same on 32-bit and 64-bit targets.
+pub trait Random: Sized {
+    /// Generate a random value using the given rng.
+    #[must_use = "please use the generated value"]
+    fn random<R>(rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized;
+}
+
+/// Ranges that a single value can be sampled from, without modulo bias.
+///
+/// Implemented for `Range` and `RangeInclusive` of every primitive integer and float type.
+pub trait SampleRange<T> {
+    /// Sample a single value from the range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty, or if it is a float range with an infinite bound.
+    #[must_use = "please use the generated value"]
+    fn sample_single<R>(self, rng: &mut R) -> T
+    where
+        R: RngCore + ?Sized;
+
+    /// Returns `true` if the range contains no values.
+    fn is_empty(&self) -> bool;
+}
+
+macro_rules! impl_random_for_ints {
+    ($($ty:ty => |$rng:ident| $draw:expr),* $(,)?) => {
+        $(
+            impl Random for $ty {
+                #[inline]
+                fn random<R>($rng: &mut R) -> Self
+                where
+                    R: RngCore + ?Sized,
+                {
+                    $draw as $ty
+                }
+            }
+        )*
+    };
+}
+
+impl_random_for_ints!(
+    u8 => |rng| rng.next_u32(),
+    u16 => |rng| rng.next_u32(),
+    u32 => |rng| rng.next_u32(),
+    u64 => |rng| rng.next_u64(),
+    u128 => |rng| (u128::from(rng.next_u64()) << 64) | u128::from(rng.next_u64()),
+    usize => |rng| rng.next_u64(),
+    i8 => |rng| rng.next_u32(),
+    i16 => |rng| rng.next_u32(),
+    i32 => |rng| rng.next_u32(),
+    i64 => |rng| rng.next_u64(),
+    i128 => |rng| u128::random(rng),
+    isize => |rng| rng.next_u64(),
+);
+
+impl Random for bool {
+    #[inline]
+    fn random<R>(rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        rng.next_u32() >> 31 == 1
+    }
+}
+
+impl Random for f32 {
+    #[inline]
+    fn random<R>(rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        (rng.next_u32() >> 8) as Self / (1_u32 << Self::MANTISSA_DIGITS) as Self
+    }
+}
+
+impl Random for f64 {
+    #[inline]
+    fn random<R>(rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        (rng.next_u64() >> 11) as Self / (1_u64 << Self::MANTISSA_DIGITS) as Self
+    }
+}
+
+impl Random for char {
+    /// Uniform over the valid scalar values, surrogates are never generated.
+    #[inline]
+    fn random<R>(rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        const SURROGATES: u32 = 0xe000 - 0xd800;
+
+        let mut value = bounded_u64(rng, u64::from(Self::MAX) + 1 - u64::from(SURROGATES)) as u32;
+        if value >= 0xd800 {
+            value += SURROGATES;
+        }
+        Self::from_u32(value).unwrap_or_default()
+    }
+}
+
+impl<T: Random> Random for Option<T> {
+    /// `Some` and `None` are equally likely, the inner value is only generated for `Some`.
+    #[inline]
+    fn random<R>(rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        bool::random(rng).then(|| T::random(rng))
+    }
+}
+
+impl<T: Random, const N: usize> Random for [T; N] {
+    #[inline]
+    fn random<R>(rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        array::from_fn(|_| T::random(rng))
+    }
+}
+
+impl Random for () {
+    #[inline]
+    fn random<R>(_rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+    }
+}
+
+macro_rules! impl_random_for_tuples {
+    ($(($($name:ident),+)),* $(,)?) => {
+        $(
+            impl<$($name: Random),+> Random for ($($name,)+) {
+                #[inline]
+                fn random<R>(rng: &mut R) -> Self
+                where
+                    R: RngCore + ?Sized,
+                {
+                    ($($name::random(rng),)+)
+                }
+            }
+        )*
+    };
+}
+
+impl_random_for_tuples!(
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H),
+    (A, B, C, D, E, F, G, H, I),
+    (A, B, C, D, E, F, G, H, I, J),
+    (A, B, C, D, E, F, G, H, I, J, K),
+    (A, B, C, D, E, F, G, H, I, J, K, L),
+);
+
+/// A uniform `u64` in `0..bound` using Lemire's multiply-and-reject method, which usually
+/// needs a single draw. If `bound` is 0, 0 is returned.
+#[inline]
+pub(crate) fn bounded_u64<R>(rng: &mut R, bound: u64) -> u64
+where
+    R: RngCore + ?Sized,
+{
+    if bound == 0 {
+        return 0;
+    }
+
+    let mut product = u128::from(rng.next_u64()) * u128::from(bound);
+    let mut low = product as u64;
+    if low < bound {
+        let threshold = bound.wrapping_neg() % bound;
+        while low < threshold {
+            product = u128::from(rng.next_u64()) * u128::from(bound);
+            low = product as u64;
+        }
+    }
+
+    (product >> 64) as u64
+}
+
+/// A uniform `u128` in `0..bound` using masked rejection. If `bound` is 0, 0 is returned.
+#[inline]
+fn bounded_u128<R>(rng: &mut R, bound: u128) -> u128
+where
+    R: RngCore + ?Sized,
+{
+    if bound == 0 {
+        return 0;
+    }
+
+    let mask = u128::MAX.checked_shr((bound - 1).leading_zeros()).unwrap_or(0);
+    loop {
+        let value = u128::random(rng) & mask;
+        if value < bound {
+            return value;
+        }
+    }
+}
+
+macro_rules! impl_sample_range_for_ints {
+    ($($ty:ty: $unsigned:ty => $wide:ty, $bounded:ident);* $(;)?) => {
+        $(
+            impl SampleRange<$ty> for Range<$ty> {
+                #[inline]
+                #[track_caller]
+                fn sample_single<R>(self, rng: &mut R) -> $ty
+                where
+                    R: RngCore + ?Sized,
+                {
+                    assert!(self.start < self.end, "cannot sample from an empty range");
+                    let width = self.end.wrapping_sub(self.start) as $unsigned as $wide;
+                    self.start.wrapping_add($bounded(rng, width) as $ty)
+                }
+
+                #[inline]
+                fn is_empty(&self) -> bool {
+                    Range::is_empty(self)
+                }
+            }
+
+            impl SampleRange<$ty> for RangeInclusive<$ty> {
+                #[inline]
+                #[track_caller]
+                fn sample_single<R>(self, rng: &mut R) -> $ty
+                where
+                    R: RngCore + ?Sized,
+                {
+                    let (start, end) = self.into_inner();
+                    assert!(start <= end, "cannot sample from an empty range");
+                    let width = (end.wrapping_sub(start) as $unsigned as $wide).wrapping_add(1);
+                    if width == 0 {
+                        // the range covers every value of the widest type
+                        return <$ty>::random(rng);
+                    }
+                    start.wrapping_add($bounded(rng, width) as $ty)
+                }
+
+                #[inline]
+                fn is_empty(&self) -> bool {
+                    RangeInclusive::is_empty(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_sample_range_for_ints!(
+    u8: u8 => u64, bounded_u64;
+    u16: u16 => u64, bounded_u64;
+    u32: u32 => u64, bounded_u64;
+    u64: u64 => u64, bounded_u64;
+    usize: usize => u64, bounded_u64;
+    i8: u8 => u64, bounded_u64;
+    i16: u16 => u64, bounded_u64;
+    i32: u32 => u64, bounded_u64;
+    i64: u64 => u64, bounded_u64;
+    isize: usize => u64, bounded_u64;
+    u128: u128 => u128, bounded_u128;
+    i128: u128 => u128, bounded_u128;
+);
+
+/// `start + (end - start) * t`, without overflowing when `end - start` doesn't fit the type.
+macro_rules! lerp {
+    ($start:expr, $end:expr, $t:expr) => {{
+        let (start, end, t) = ($start, $end, $t);
+        let scale = end - start;
+        if scale.is_finite() {
+            start + scale * t
+        } else {
+            // the halves are at most half as far apart, so their difference always fits
+            (start / 2.0 + (end / 2.0 - start / 2.0) * t) * 2.0
+        }
+    }};
+}
+
+macro_rules! impl_sample_range_for_floats {
+    ($($ty:ty => |$rng:ident| $closed_unit:expr),* $(,)?) => {
+        $(
+            // `mul_add` lives in `std`, so it can't be used here.
+            #[allow(clippy::suboptimal_flops)]
+            impl SampleRange<$ty> for Range<$ty> {
+                #[inline]
+                #[track_caller]
+                fn sample_single<R>(self, rng: &mut R) -> $ty
+                where
+                    R: RngCore + ?Sized,
+                {
+                    assert!(self.start < self.end, "cannot sample from an empty range");
+                    assert!(self.start.is_finite() && self.end.is_finite(), "cannot sample from a range with an infinite bound");
+                    loop {
+                        // rounding can land exactly on `end`, draw again when it does
+                        let value = lerp!(self.start, self.end, <$ty>::random(rng));
+                        if value < self.end {
+                            return value;
+                        }
+                    }
+                }
+
+                #[inline]
+                fn is_empty(&self) -> bool {
+                    Range::is_empty(self)
+                }
+            }
+
+            // `mul_add` lives in `std`, so it can't be used here.
+            #[allow(clippy::suboptimal_flops)]
+            impl SampleRange<$ty> for RangeInclusive<$ty> {
+                #[inline]
+                #[track_caller]
+                fn sample_single<R>(self, $rng: &mut R) -> $ty
+                where
+                    R: RngCore + ?Sized,
+                {
+                    let (start, end) = self.into_inner();
+                    assert!(start <= end, "cannot sample from an empty range");
+                    assert!(start.is_finite() && end.is_finite(), "cannot sample from a range with an infinite bound");
+                    lerp!(start, end, $closed_unit).min(end)
+                }
+
+                #[inline]
+                fn is_empty(&self) -> bool {
+                    RangeInclusive::is_empty(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_sample_range_for_floats!(
+    f32 => |rng| (rng.next_u32() >> 8) as f32 / ((1_u32 << f32::MANTISSA_DIGITS) - 1) as f32,
+    f64 => |rng| (rng.next_u64() >> 11) as f64 / ((1_u64 << f64::MANTISSA_DIGITS) - 1) as f64,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::SampleRange;
+    use crate::mock::ConstRng;
+    use crate::rng::XorShift128;
+
+    #[test]
+    fn float_ranges_wider_than_the_type() {
+        let mut rng = XorShift128::new(1);
+        for _ in 0..1000 {
+            let value = (f64::MIN..f64::MAX).sample_single(&mut rng);
+            assert!((f64::MIN..f64::MAX).contains(&value), "{value}");
+            let value = (f32::MIN..=f32::MAX).sample_single(&mut rng);
+            assert!(value.is_finite(), "{value}");
+        }
+
+        assert_eq!((f64::MIN..=f64::MAX).sample_single(&mut ConstRng(0)), f64::MIN);
+        assert_eq!((f64::MIN..=f64::MAX).sample_single(&mut ConstRng(u64::MAX)), f64::MAX);
+    }
+
+    #[test]
+    fn narrow_float_ranges_are_unchanged() {
+        assert_eq!((1.0..3.0).sample_single(&mut ConstRng(1 << 63)), 2.0);
+        assert_eq!((-1.0_f32..=1.0).sample_single(&mut ConstRng(0)), -1.0);
+    }
+
+    #[test]
+    #[should_panic = "infinite bound"]
+    fn float_ranges_with_an_infinite_bound() {
+        let _ = (0.0..f64::INFINITY).sample_single(&mut XorShift128::new(1));
+    }
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod derive_tests {
+    use super::{Random, SampleRange};
+    use crate::rng::XorShift128;
+    use rand_core::RngCore;
+
+    const DRAWS: usize = 1000;
+
+    #[derive(Random, Debug, PartialEq)]
+    struct Ranged {
+        #[random(range = 10..20)]
+        int: u32,
+        #[random(range = -1.0..=1.0)]
+        float: f64,
+        #[random(skip)]
+        skipped: Option<u64>,
+        plain: u16,
+    }
+
+    #[derive(Random, Debug, PartialEq)]
+    enum Weighted {
+        #[random(weight = 3)]
+        Common,
+        Rare(u8),
+        #[random(weight = 0)]
+        Never,
+        #[random(skip)]
+        Skipped {
+            value: u64,
+        },
+    }
+
+    #[derive(Random, Debug, PartialEq)]
+    struct Tuple(u32, #[random(range = 0..=5)] i8, bool);
+
+    #[derive(Random, Debug, PartialEq)]
+    struct Unit;
+
+    #[derive(Random, Debug, PartialEq)]
+    enum Single {
+        Only(u64),
+    }
+
+    #[derive(Random, Debug, PartialEq)]
+    struct Pair<T> {
+        first: T,
+        second: T,
+    }
+
+    #[derive(Random, Debug, PartialEq)]
+    enum Either<L, R> {
+        Left(L),
+        Right(R),
+    }
+
+    #[test]
+    fn fields_follow_their_attributes() {
+        let mut rng = XorShift128::new(1);
+        let mut expected = XorShift128::new(1);
+        for _ in 0..DRAWS {
+            let value = Ranged::random(&mut rng);
+            assert!((10..20).contains(&value.int), "{value:?}");
+            assert!((-1.0..=1.0).contains(&value.float), "{value:?}");
+            assert_eq!(value.skipped, None, "a skipped field should be its default");
+
+            // the fields are drawn in order, and skipped fields draw nothing
+            let int = (10..20).sample_single(&mut expected);
+            let float = (-1.0..=1.0).sample_single(&mut expected);
+            let plain = u16::random(&mut expected);
+            assert_eq!(
+                value,
+                Ranged {
+                    int,
+                    float,
+                    skipped: None,
+                    plain
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn variants_follow_their_weights() {
+        let mut rng = XorShift128::new(2);
+        let mut common = 0;
+        for _ in 0..DRAWS {
+            match Weighted::random(&mut rng) {
+                Weighted::Common => common += 1,
+                Weighted::Rare(_) => {}
+                other => panic!("{other:?} has no weight and should never be picked"),
+            }
+        }
+        // 3 out of 4 picks on average
+        assert!((650..850).contains(&common), "picked `Common` {common} times out of {DRAWS}");
+    }
+
+    #[test]
+    fn tuple_and_unit_structs() {
+        let mut rng = XorShift128::new(3);
+        let mut expected = XorShift128::new(3);
+        for _ in 0..DRAWS {
+            let Tuple(a, b, c) = Tuple::random(&mut rng);
+            assert!((0..=5).contains(&b), "{b}");
+            assert_eq!(
+                (a, b, c),
+                (
+                    u32::random(&mut expected),
+                    (0..=5).sample_single(&mut expected),
+                    bool::random(&mut expected)
+                )
+            );
+        }
+
+        let before = rng.get_current_state();
+        assert_eq!(Unit::random(&mut rng), Unit);
+        assert_eq!(rng.get_current_state(), before, "a unit struct shouldn't draw from the rng");
+
+        // a single variant is picked without a draw, only its fields draw
+        let value = Single::random(&mut rng);
+        assert_eq!(value, Single::Only(expected.next_u64()));
+    }
+
+    #[test]
+    fn generic_types() {
+        let mut rng = XorShift128::new(4);
+        let mut expected = XorShift128::new(4);
+        let pair: Pair<u64> = Pair::random(&mut rng);
+        assert_eq!(
+            pair,
+            Pair {
+                first: expected.next_u64(),
+                second: expected.next_u64()
+            }
+        );
+
+        let mut lefts = 0;
+        for _ in 0..DRAWS {
+            if let Either::<u8, Pair<bool>>::Left(_) = Either::random(&mut rng) {
+                lefts += 1;
+            }
+        }
+        assert!((400..600).contains(&lefts), "picked `Left` {lefts} times out of {DRAWS}");
+    }
+}
@@ -0,0 +1,479 @@
+use crate::error::RngError;
+use crate::genrandom::GenRandom;
+use rand_core::SeedableRng;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec;
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::{string::String, vec::Vec};
+
+const POOL_SIZE: usize = 4;
+const INIT_A: u32 = 0x43b0_d7e5;
+const MULT_A: u32 = 0x931e_8875;
+const INIT_B: u32 = 0x8b51_f9dd;
+const MULT_B: u32 = 0x58f3_8ded;
+const MIX_MULT_L: u32 = 0xca01_f9dd;
+const MIX_MULT_R: u32 = 0x4973_f715;
+const XSHIFT: u32 = 16;
+
+/// Mixes arbitrary-length entropy into seeds for any engine, compatible with numpy's
+/// `numpy.random.SeedSequence`.
+///
+/// The entropy and spawn key are turned into 32-bit words exactly like numpy does (see
+/// [`SeedEntropy`]), and hashed into a pool of 4 words. The pool is then expanded into as
+/// many words as an engine's seed needs, so a seed shared with Python code produces the
+/// same `generate_state` output on both sides.
+///
+/// ```rust
+/// use voxell_rng::rng::XorShift128;
+/// use voxell_rng::seed_sequence::SeedSequence;
+///
+/// // same as `SeedSequence([3735928559, 195939070, 229505742, 305419896]).generate_state(4)`
+/// let seq = SeedSequence::new(&[3735928559_u32, 195939070, 229505742, 305419896]);
+/// let mut state = [0; 4];
+/// seq.generate_state_u32(&mut state);
+/// assert_eq!(state, [3914649087, 576849849, 3593928901, 2229911004]);
+///
+/// let mut state = [0; 2];
+/// seq.generate_state_u64(&mut state);
+/// assert_eq!(state, [2477551240072187391, 9577394838764454085]);
+///
+/// // independent child sequences, one per worker
+/// let mut seq = SeedSequence::new("CrystalCaves42");
+/// let workers: Vec<XorShift128> = seq.spawn(4).iter().map(SeedSequence::create_rng).collect();
+/// ```
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct SeedSequence {
+    entropy: Vec<u32>,
+    spawn_key: Vec<u32>,
+    n_children_spawned: u64,
+    pool: [u32; POOL_SIZE],
+}
+
+impl SeedSequence {
+    /// Create a new seed sequence from the given entropy.
+    #[inline]
+    #[must_use]
+    pub fn new<E>(entropy: &E) -> Self
+    where
+        E: SeedEntropy + ?Sized,
+    {
+        Self::with_spawn_key::<E, [u32]>(entropy, &[])
+    }
+
+    /// Create a new seed sequence from the given entropy and spawn key, the path of child
+    /// indices that [`SeedSequence::spawn`] would have taken to reach it.
+    #[inline]
+    #[must_use]
+    pub fn with_spawn_key<E, K>(entropy: &E, spawn_key: &K) -> Self
+    where
+        E: SeedEntropy + ?Sized,
+        K: SeedEntropy + ?Sized,
+    {
+        let mut entropy_words = Vec::new();
+        entropy.append_words(&mut entropy_words);
+        let mut spawn_key_words = Vec::new();
+        spawn_key.append_words(&mut spawn_key_words);
+
+        Self::from_words(entropy_words, spawn_key_words)
+    }
+
+    /// Create a new seed sequence from 128 bits of entropy from the
+    /// [`GlobalEntropy`](crate::entropy::GlobalEntropy) source, which is the OS unless a hook was registered.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entropy source fails to generate random bytes.
+    #[inline]
+    pub fn from_os_entropy() -> Result<Self, RngError> {
+        Ok(Self::new(&u128::get_random()?))
+    }
+
+    #[inline]
+    fn from_words(entropy: Vec<u32>, spawn_key: Vec<u32>) -> Self {
+        let mut assembled = entropy.clone();
+        if !spawn_key.is_empty() && assembled.len() < POOL_SIZE {
+            // keeps `[x]` with spawn key `[y]` apart from the entropy `[x, y]`
+            assembled.resize(POOL_SIZE, 0);
+        }
+        assembled.extend_from_slice(&spawn_key);
+
+        Self {
+            entropy,
+            spawn_key,
+            n_children_spawned: 0,
+            pool: mix_entropy(&assembled),
+        }
+    }
+
+    /// The entropy this sequence was created from, as 32-bit words.
+    #[inline]
+    #[must_use]
+    pub fn entropy(&self) -> &[u32] {
+        &self.entropy
+    }
+
+    /// The spawn key of this sequence, as 32-bit words.
+    #[inline]
+    #[must_use]
+    pub fn spawn_key(&self) -> &[u32] {
+        &self.spawn_key
+    }
+
+    /// The number of children spawned from this sequence so far.
+    #[inline]
+    #[must_use]
+    pub const fn n_children_spawned(&self) -> u64 {
+        self.n_children_spawned
+    }
+
+    /// Fill `state` with words expanded from the pool, same as numpy's
+    /// `generate_state(len, dtype=np.uint32)`.
+    #[inline]
+    pub fn generate_state_u32(&self, state: &mut [u32]) {
+        let mut hash_const = INIT_B;
+        for (word, &pool) in state.iter_mut().zip(self.pool.iter().cycle()) {
+            let mut value = pool ^ hash_const;
+            hash_const = hash_const.wrapping_mul(MULT_B);
+            value = value.wrapping_mul(hash_const);
+            *word = value ^ (value >> XSHIFT);
+        }
+    }
+
+    /// Fill `state` with words expanded from the pool, same as numpy's
+    /// `generate_state(len, dtype=np.uint64)`.
+    ///
+    /// Each `u64` is made of two consecutive `u32` words, low word first.
+    #[inline]
+    pub fn generate_state_u64(&self, state: &mut [u64]) {
+        let mut words = vec![0; state.len() * 2];
+        self.generate_state_u32(&mut words);
+
+        for (word, pair) in state.iter_mut().zip(words.chunks_exact(2)) {
+            *word = pair.iter().rev().fold(0, |acc, &half| (acc << 32) | u64::from(half));
+        }
+    }
+
+    /// Generate a seed for the engine `R`, made of the little-endian bytes of
+    /// [`SeedSequence::generate_state_u32`].
+    #[inline]
+    #[must_use]
+    pub fn generate_seed<R>(&self) -> R::Seed
+    where
+        R: SeedableRng,
+    {
+        let mut seed = R::Seed::default();
+        let bytes = seed.as_mut();
+
+        let mut words = vec![0; bytes.len().div_ceil(4)];
+        self.generate_state_u32(&mut words);
+
+        for (chunk, word) in bytes.chunks_mut(4).zip(words) {
+            chunk.copy_from_slice(&word.to_le_bytes()[..chunk.len()]);
+        }
+        seed
+    }
+
+    /// Create an instance of the engine `R` from [`SeedSequence::generate_seed`].
+    #[inline]
+    #[must_use]
+    pub fn create_rng<R>(&self) -> R
+    where
+        R: SeedableRng,
+    {
+        R::from_seed(self.generate_seed::<R>())
+    }
+
+    /// Spawn `n` independent child sequences, same as numpy's `spawn(n)`.
+    ///
+    /// Children share this sequence's entropy, and their spawn key is this sequence's spawn
+    /// key followed by the child's index. Indices keep counting up across calls, so spawning
+    /// twice never produces the same child.
+    #[inline]
+    pub fn spawn(&mut self, n: usize) -> Vec<Self> {
+        let start = self.n_children_spawned;
+        self.n_children_spawned += n as u64;
+
+        (start..self.n_children_spawned)
+            .map(|index| {
+                let mut spawn_key = self.spawn_key.clone();
+                index.append_words(&mut spawn_key);
+                Self::from_words(self.entropy.clone(), spawn_key)
+            })
+            .collect()
+    }
+}
+
+#[inline]
+const fn hashmix(value: u32, hash_const: &mut u32) -> u32 {
+    let mut value = value ^ *hash_const;
+    *hash_const = hash_const.wrapping_mul(MULT_A);
+    value = value.wrapping_mul(*hash_const);
+    value ^ (value >> XSHIFT)
+}
+
+#[inline]
+const fn mix(x: u32, y: u32) -> u32 {
+    let result = MIX_MULT_L.wrapping_mul(x).wrapping_sub(MIX_MULT_R.wrapping_mul(y));
+    result ^ (result >> XSHIFT)
+}
+
+#[inline]
+fn mix_entropy(entropy: &[u32]) -> [u32; POOL_SIZE] {
+    let mut hash_const = INIT_A;
+    let mut pool = [0; POOL_SIZE];
+    for (i, slot) in pool.iter_mut().enumerate() {
+        *slot = hashmix(entropy.get(i).copied().unwrap_or(0), &mut hash_const);
+    }
+
+    for src in 0..POOL_SIZE {
+        for dst in 0..POOL_SIZE {
+            if src != dst {
+                pool[dst] = mix(pool[dst], hashmix(pool[src], &mut hash_const));
+            }
+        }
+    }
+
+    for &word in entropy.iter().skip(POOL_SIZE) {
+        for slot in &mut pool {
+            *slot = mix(*slot, hashmix(word, &mut hash_const));
+        }
+    }
+
+    pool
+}
+
+/// Entropy that a [`SeedSequence`] can be created from.
+///
+/// Values are turned into 32-bit words the same way numpy coerces them:
+///
+/// - integers are split into 32-bit words, least significant first, with the most significant
+///   zero words dropped. Zero is a single zero word. numpy rejects negative integers, here
+///   they are reinterpreted as their unsigned two's complement value.
+/// - numeric strings are parsed like numpy parses them: `0x` means hexadecimal, a leading `0`
+///   means octal, and anything else is decimal. numpy rejects other strings, here they are
+///   their UTF-8 bytes packed into little-endian words, with the last word zero-padded.
+/// - slices, arrays and tuples are the concatenation of their elements' words.
+pub trait SeedEntropy {
+    /// Append the 32-bit words of this entropy to `words`.
+    fn append_words(&self, words: &mut Vec<u32>);
+}
+
+#[inline]
+fn append_int_words(mut value: u128, words: &mut Vec<u32>) {
+    loop {
+        words.push(value as u32);
+        value >>= 32;
+        if value == 0 {
+            return;
+        }
+    }
+}
+
+macro_rules! impl_seed_entropy_for_ints {
+    ($($ty:ty => $unsigned:ty),* $(,)?) => {
+        $(
+            impl SeedEntropy for $ty {
+                #[inline]
+                #[allow(clippy::cast_sign_loss)]
+                fn append_words(&self, words: &mut Vec<u32>) {
+                    append_int_words(*self as $unsigned as u128, words);
+                }
+            }
+        )*
+    };
+}
+
+impl_seed_entropy_for_ints!(
+    u8 => u8, u16 => u16, u32 => u32, u64 => u64, u128 => u128, usize => usize,
+    i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize,
+);
+
+/// Parse an arbitrarily large unsigned integer into 32-bit words, least significant first.
+#[inline]
+fn parse_int_words(digits: &str, radix: u32) -> Option<Vec<u32>> {
+    if digits.is_empty() {
+        return None;
+    }
+
+    let mut words: Vec<u32> = Vec::new();
+    for c in digits.chars() {
+        let mut carry = u64::from(c.to_digit(radix)?);
+        for word in &mut words {
+            let value = u64::from(*word) * u64::from(radix) + carry;
+            *word = value as u32;
+            carry = value >> 32;
+        }
+        if carry != 0 {
+            words.push(carry as u32);
+        }
+    }
+
+    if words.is_empty() {
+        words.push(0);
+    }
+    Some(words)
+}
+
+impl SeedEntropy for str {
+    #[inline]
+    fn append_words(&self, words: &mut Vec<u32>) {
+        let parsed = match self.strip_prefix("0x") {
+            Some(hex) => parse_int_words(hex, 16),
+            None if self.starts_with('0') => parse_int_words(self, 8),
+            None => parse_int_words(self, 10),
+        };
+
+        let Some(parsed) = parsed else {
+            for chunk in self.as_bytes().chunks(4) {
+                let mut word = [0; 4];
+                word[..chunk.len()].copy_from_slice(chunk);
+                words.push(u32::from_le_bytes(word));
+            }
+            return;
+        };
+
+        words.extend(parsed);
+    }
+}
+
+impl SeedEntropy for String {
+    #[inline]
+    fn append_words(&self, words: &mut Vec<u32>) {
+        self.as_str().append_words(words);
+    }
+}
+
+impl<T: SeedEntropy + ?Sized> SeedEntropy for &T {
+    #[inline]
+    fn append_words(&self, words: &mut Vec<u32>) {
+        (**self).append_words(words);
+    }
+}
+
+impl<T: SeedEntropy> SeedEntropy for [T] {
+    #[inline]
+    fn append_words(&self, words: &mut Vec<u32>) {
+        for item in self {
+            item.append_words(words);
+        }
+    }
+}
+
+impl<T: SeedEntropy, const N: usize> SeedEntropy for [T; N] {
+    #[inline]
+    fn append_words(&self, words: &mut Vec<u32>) {
+        self.as_slice().append_words(words);
+    }
+}
+
+impl<T: SeedEntropy> SeedEntropy for Vec<T> {
+    #[inline]
+    fn append_words(&self, words: &mut Vec<u32>) {
+        self.as_slice().append_words(words);
+    }
+}
+
+macro_rules! impl_seed_entropy_for_tuples {
+    ($(($($name:ident),+)),* $(,)?) => {
+        $(
+            impl<$($name: SeedEntropy),+> SeedEntropy for ($($name,)+) {
+                #[inline]
+                #[allow(non_snake_case)]
+                fn append_words(&self, words: &mut Vec<u32>) {
+                    let &($(ref $name,)+) = self;
+                    $($name.append_words(words);)+
+                }
+            }
+        )*
+    };
+}
+
+impl_seed_entropy_for_tuples!(
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H),
+);
+
+#[cfg(test)]
+mod tests {
+    use super::{SeedEntropy, SeedSequence, Vec};
+    use crate::rng::XorShift128;
+
+    fn state(seq: &SeedSequence) -> [u32; 4] {
+        let mut state = [0; 4];
+        seq.generate_state_u32(&mut state);
+        state
+    }
+
+    fn words<E: SeedEntropy + ?Sized>(entropy: &E) -> Vec<u32> {
+        let mut words = Vec::new();
+        entropy.append_words(&mut words);
+        words
+    }
+
+    /// `SeedSequence(0).generate_state(4)` from the numpy docs
+    #[test]
+    fn numpy_seed_zero() {
+        assert_eq!(state(&SeedSequence::new(&0_u32)), [2968811710, 3677149159, 745650761, 2884920346]);
+    }
+
+    /// the reference data and zero padding cases of numpy's `test_seed_sequence.py`
+    #[test]
+    fn numpy_reference_data() {
+        let seq = SeedSequence::new(&[3735928559_u32, 195939070, 229505742, 305419896]);
+        assert_eq!(state(&seq), [3914649087, 576849849, 3593928901, 2229911004]);
+        let mut state64 = [0; 2];
+        seq.generate_state_u64(&mut state64);
+        assert_eq!(state64, [2477551240072187391, 9577394838764454085]);
+
+        let expected42 = [3444837047, 2669555309, 2046530742, 3581440988];
+        assert_eq!(state(&SeedSequence::new(&42_u8)), expected42);
+        assert_ne!(state(&SeedSequence::new(&(42_u64 << 32))), expected42);
+        // gh-16539, the implicit zero padding doesn't collide with a spawn key of 0
+        assert_ne!(state(&SeedSequence::with_spawn_key(&42_u8, &0_u8)), expected42);
+    }
+
+    #[test]
+    fn entropy_words_match_numpy_coercion() {
+        assert_eq!(words(&0_u64), [0]);
+        assert_eq!(words(&((5_u128 << 64) | 7)), [7, 0, 5]);
+        assert_eq!(words(&-1_i16), [0xffff]);
+        assert_eq!(words("0x100000000"), [0, 1]);
+        assert_eq!(words("010"), [8]);
+        assert_eq!(words("18446744073709551617"), [1, 0, 1]);
+        assert_eq!(words("abcde"), [0x6463_6261, 0x65]);
+        assert_eq!(words(&(1_u8, [2_u64 << 32])), [1, 0, 2]);
+    }
+
+    #[test]
+    fn spawned_children_are_keyed_by_index() {
+        let mut seq = SeedSequence::new(&7_u32);
+        let first = seq.spawn(2);
+        let second = seq.spawn(1);
+        assert_eq!(seq.n_children_spawned(), 3);
+
+        for (index, child) in first.iter().chain(&second).enumerate() {
+            assert_eq!(child.entropy(), [7]);
+            assert_eq!(child.spawn_key(), [index as u32]);
+            assert_eq!(state(child), state(&SeedSequence::with_spawn_key(&7_u32, &(index as u32))));
+        }
+        assert_ne!(state(&first[0]), state(&first[1]));
+        assert_ne!(state(&first[0]), state(&seq));
+    }
+
+    #[test]
+    fn generated_seeds_are_little_endian_words() {
+        let seq = SeedSequence::new(&0_u32);
+        let seed: [u8; 16] = seq.generate_seed::<XorShift128>();
+        assert_eq!(seed[..4], 2968811710_u32.to_le_bytes());
+        assert_eq!(seed[12..], 2884920346_u32.to_le_bytes());
+    }
+}
@@ -0,0 +1,299 @@
+// Signed integers are sign-extended into words on purpose, so `-1_i8` and `-1_i64` are the
+// same key.
+#![allow(clippy::cast_sign_loss)]
+
+use crate::rng::SplitMix64;
+use rand_core::SeedableRng;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::{string::String, vec::Vec};
+
+/// Marks the start of a string key, so strings don't derive the same seeds as the integers
+/// that happen to share their bytes.
+const STR_TAG: u64 = 0x7374_725f_6b65_7973;
+
+/// Derives independent, reproducible RNGs from a root seed and a logical key path.
+///
+/// The seed of a child depends only on the parent's seed and the key, never on how many
+/// RNGs were created before it or on which thread creates it. Work that is split across a
+/// thread pool can draw from `tree.rng_for(&(chunk_x, chunk_z, "trees"))` and get the same
+/// numbers no matter how the tasks are scheduled.
+///
+/// The derivation is fixed and documented on [`SeedKey`], so derived seeds stay the same
+/// across platforms and crate versions.
+///
+/// ```rust
+/// use voxell_rng::prelude::*;
+/// use voxell_rng::rng::XorShift128;
+/// use voxell_rng::seed_tree::SeedTree;
+///
+/// let world = SeedTree::new(0xcafebabe);
+///
+/// // tuples are key paths, so these are the same generator
+/// let mut trees: XorShift128 = world.rng_for(&(3_i32, -7_i32, "trees"));
+/// let mut same: XorShift128 = world.child(&3_i32).child(&-7_i32).rng_for(&"trees");
+/// assert_eq!(trees.next_u64(), same.next_u64());
+///
+/// // while a different key gives an unrelated one
+/// let mut rocks: XorShift128 = world.rng_for(&(3_i32, -7_i32, "rocks"));
+/// ```
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct SeedTree {
+    seed: u64,
+}
+
+impl SeedTree {
+    /// Create a new tree rooted at `seed`.
+    #[inline]
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    /// The seed of this node of the tree.
+    #[inline]
+    #[must_use]
+    pub const fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Derive the child of this node under `key`.
+    #[inline]
+    #[must_use]
+    pub fn child<K>(&self, key: &K) -> Self
+    where
+        K: SeedKey + ?Sized,
+    {
+        Self::new(key.derive_seed(self.seed))
+    }
+
+    /// Create an RNG from this node's seed.
+    ///
+    /// The engine is filled from a [`SplitMix64`] RNG wrapping the seed, so every engine gets
+    /// a full, well mixed state.
+    #[inline]
+    #[must_use]
+    pub fn rng<R>(&self) -> R
+    where
+        R: SeedableRng,
+    {
+        R::from_rng(&mut SplitMix64::wrap(self.seed))
+    }
+
+    /// Create an RNG from the child of this node under `key`, same as `self.child(key).rng()`.
+    #[inline]
+    #[must_use]
+    pub fn rng_for<R, K>(&self, key: &K) -> R
+    where
+        R: SeedableRng,
+        K: SeedKey + ?Sized,
+    {
+        self.child(key).rng()
+    }
+}
+
+/// Keys that can be used to derive child seeds in a [`SeedTree`].
+///
+/// Keys are mixed into the seed one 64-bit word at a time with
+/// `seed = splitmix64_finalizer((seed + 0x9e3779b97f4a7c15) ^ word)`:
+///
+/// - integers, `bool` and `char` are a single word, signed integers are sign-extended first,
+///   so `7_u8` and `7_u64` are the same key. 128-bit integers are two words, low word first.
+/// - strings are a tag word, their length in bytes, and then their bytes in little-endian
+///   words, with the last word zero-padded.
+/// - tuples, arrays and slices are key paths: each element is mixed in order, so
+///   `child(&(a, b))` is the same as `child(&a).child(&b)`.
+pub trait SeedKey {
+    /// Mix this key into `seed`, returning the seed of the child.
+    #[must_use]
+    fn derive_seed(&self, seed: u64) -> u64;
+}
+
+/// Mix a single word into a seed, see [`SeedKey`].
+#[inline]
+const fn mix_word(seed: u64, word: u64) -> u64 {
+    let mut x = seed.wrapping_add(0x9e37_79b9_7f4a_7c15) ^ word;
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+macro_rules! impl_seed_key_for_words {
+    ($($ty:ty => $word:ty),* $(,)?) => {
+        $(
+            impl SeedKey for $ty {
+                #[inline]
+                fn derive_seed(&self, seed: u64) -> u64 {
+                    mix_word(seed, *self as $word as u64)
+                }
+            }
+        )*
+    };
+}
+
+impl_seed_key_for_words!(u8 => u64, u16 => u64, u32 => u64, u64 => u64, usize => u64);
+impl_seed_key_for_words!(i8 => i64, i16 => i64, i32 => i64, i64 => i64, isize => i64);
+
+impl SeedKey for u128 {
+    #[inline]
+    fn derive_seed(&self, seed: u64) -> u64 {
+        mix_word(mix_word(seed, *self as u64), (*self >> 64) as u64)
+    }
+}
+
+impl SeedKey for i128 {
+    #[inline]
+    fn derive_seed(&self, seed: u64) -> u64 {
+        (*self as u128).derive_seed(seed)
+    }
+}
+
+impl SeedKey for bool {
+    #[inline]
+    fn derive_seed(&self, seed: u64) -> u64 {
+        mix_word(seed, u64::from(*self))
+    }
+}
+
+impl SeedKey for char {
+    #[inline]
+    fn derive_seed(&self, seed: u64) -> u64 {
+        mix_word(seed, u64::from(*self))
+    }
+}
+
+impl SeedKey for str {
+    #[inline]
+    fn derive_seed(&self, seed: u64) -> u64 {
+        let mut seed = mix_word(mix_word(seed, STR_TAG), self.len() as u64);
+        for chunk in self.as_bytes().chunks(8) {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            seed = mix_word(seed, u64::from_le_bytes(word));
+        }
+        seed
+    }
+}
+
+impl SeedKey for String {
+    #[inline]
+    fn derive_seed(&self, seed: u64) -> u64 {
+        self.as_str().derive_seed(seed)
+    }
+}
+
+impl<T: SeedKey + ?Sized> SeedKey for &T {
+    #[inline]
+    fn derive_seed(&self, seed: u64) -> u64 {
+        (**self).derive_seed(seed)
+    }
+}
+
+impl<T: SeedKey> SeedKey for [T] {
+    #[inline]
+    fn derive_seed(&self, seed: u64) -> u64 {
+        self.iter().fold(seed, |seed, key| key.derive_seed(seed))
+    }
+}
+
+impl<T: SeedKey, const N: usize> SeedKey for [T; N] {
+    #[inline]
+    fn derive_seed(&self, seed: u64) -> u64 {
+        self.as_slice().derive_seed(seed)
+    }
+}
+
+impl<T: SeedKey> SeedKey for Vec<T> {
+    #[inline]
+    fn derive_seed(&self, seed: u64) -> u64 {
+        self.as_slice().derive_seed(seed)
+    }
+}
+
+macro_rules! impl_seed_key_for_tuples {
+    ($(($($name:ident),+)),* $(,)?) => {
+        $(
+            impl<$($name: SeedKey),+> SeedKey for ($($name,)+) {
+                #[inline]
+                #[allow(non_snake_case)]
+                fn derive_seed(&self, seed: u64) -> u64 {
+                    let &($(ref $name,)+) = self;
+                    $(let seed = $name.derive_seed(seed);)+
+                    seed
+                }
+            }
+        )*
+    };
+}
+
+impl_seed_key_for_tuples!(
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H),
+);
+
+#[cfg(test)]
+mod tests {
+    use super::{mix_word, SeedKey, SeedTree, String};
+
+    #[test]
+    fn mix_word_is_the_splitmix64_step() {
+        // the first output of `SplitMix64::wrap(0)`
+        assert_eq!(mix_word(0, 0), 0xe220_a839_7b1d_cdaf);
+    }
+
+    #[test]
+    fn derived_seeds_are_pinned() {
+        let world = SeedTree::new(0xcafe_babe);
+        assert_eq!(world.child(&(3_i32, -7_i32, "trees")).seed(), 0xd5a4_91ff_aa81_1c68);
+        assert_eq!(SeedTree::new(0).child(&"").seed(), 0xea96_43b3_6d07_1152);
+        assert_eq!(SeedTree::new(0).child(&5_u128).seed(), 0xad25_ec62_2da1_50b8);
+    }
+
+    #[test]
+    fn the_same_path_derives_the_same_seed() {
+        let world = SeedTree::new(42);
+        let path = (3_i32, -7_i32, "trees");
+        assert_eq!(world.child(&path), world.child(&path));
+        assert_eq!(world.child(&path), world.child(&3_i32).child(&-7_i32).child(&"trees"));
+        assert_eq!(world.child(&[1_u8, 2, 3]), world.child(&(1_u8, 2_u8, 3_u8)));
+        assert_eq!(world.child(&String::from("trees")), world.child(&"trees"));
+        assert_eq!(world.child(&-1_i8), world.child(&-1_i64), "signed keys are sign-extended");
+        assert_eq!(world.child(&7_u8), world.child(&7_u64));
+    }
+
+    #[test]
+    fn sibling_and_child_keys_differ() {
+        let world = SeedTree::new(42);
+        let siblings = [
+            world.child(&0_u64),
+            world.child(&1_u64),
+            world.child(&"0"),
+            world.child(&""),
+            world.child(&0_u128),
+        ];
+        for (i, a) in siblings.iter().enumerate() {
+            assert_ne!(a.seed(), world.seed(), "a child should differ from its parent");
+            for b in siblings.iter().skip(i + 1) {
+                assert_ne!(a, b, "sibling keys should derive different seeds");
+            }
+        }
+
+        let child = world.child(&1_u64);
+        assert_ne!(child.child(&1_u64), child, "a grandchild should differ from its parent");
+        assert_ne!(
+            child.child(&2_u64),
+            world.child(&2_u64),
+            "the same key under different parents should differ"
+        );
+        assert_ne!(world.child(&(1_u64, 2_u64)), world.child(&(2_u64, 1_u64)), "the order of a path matters");
+        assert_ne!("ab".derive_seed(0), ("a", "b").derive_seed(0), "string keys aren't concatenated");
+    }
+}
@@ -1,5 +1,9 @@
 pub use select_random::*;
+pub use select_random_iter::*;
 pub use shuffle::*;
+pub use weighted_tree::*;
 
 mod select_random;
+mod select_random_iter;
 mod shuffle;
+mod weighted_tree;
@@ -8,9 +8,6 @@ use alloc::vec::Vec;
 #[cfg(feature = "std")]
 use std::vec::Vec;
 
-// TODO: selectors that return impl Iterator<Item = &T> and not Vec<&T> so we can
-// get rid of references when we're working with types that are copy.
-
 /// trait for configuring how to select
 pub trait Selector<'rng, T, R>
 where
@@ -78,6 +75,15 @@ where
 /// - [`MultiSelectorImmutNonoverlapArray`]
 /// - [`MultiSelectorMutNonoverlap`]
 /// - [`MultiSelectorMutNonoverlapArray`]
+/// - [`MultiSelectorImmutOverlapIter`]
+/// - [`MultiSelectorCopiedOverlapIter`]
+/// - [`MultiSelectorIndexOverlapIter`]
+/// - [`MultiSelectorImmutNonoverlapIter`]
+/// - [`MultiSelectorCopiedNonoverlapIter`]
+/// - [`MultiSelectorIndexNonoverlapIter`]
+/// - [`WeightedSelectorOneImmut`]
+/// - [`WeightedMultiSelectorImmutOverlap`]
+/// - [`WeightedMultiSelectorImmutNonoverlap`]
 //
 #[inline]
 pub fn select_random_with_rng<'s, 'rng, T, S, R>(config: S, input: S::Input<'s>, rng: &'rng mut R, f: impl FnMut(&mut R) -> usize) -> S::Output<'s>
@@ -99,6 +105,15 @@ where
 /// - [`MultiSelectorImmutNonoverlapArray`]
 /// - [`MultiSelectorMutNonoverlap`]
 /// - [`MultiSelectorMutNonoverlapArray`]
+/// - [`MultiSelectorImmutOverlapIter`]
+/// - [`MultiSelectorCopiedOverlapIter`]
+/// - [`MultiSelectorIndexOverlapIter`]
+/// - [`MultiSelectorImmutNonoverlapIter`]
+/// - [`MultiSelectorCopiedNonoverlapIter`]
+/// - [`MultiSelectorIndexNonoverlapIter`]
+/// - [`WeightedSelectorOneImmut`]
+/// - [`WeightedMultiSelectorImmutOverlap`]
+/// - [`WeightedMultiSelectorImmutNonoverlap`]
 //
 // NB: investigate: why does unsize coercion (&[T; N] -> &[T]) fail when `input` arg is before `config` arg?
 #[inline]
@@ -151,6 +166,15 @@ where
     /// - [`MultiSelectorImmutNonoverlapArray`]
     /// - [`MultiSelectorMutNonoverlap`]
     /// - [`MultiSelectorMutNonoverlapArray`]
+    /// - [`MultiSelectorImmutOverlapIter`]
+    /// - [`MultiSelectorCopiedOverlapIter`]
+    /// - [`MultiSelectorIndexOverlapIter`]
+    /// - [`MultiSelectorImmutNonoverlapIter`]
+    /// - [`MultiSelectorCopiedNonoverlapIter`]
+    /// - [`MultiSelectorIndexNonoverlapIter`]
+    /// - [`WeightedSelectorOneImmut`]
+    /// - [`WeightedMultiSelectorImmutOverlap`]
+    /// - [`WeightedMultiSelectorImmutNonoverlap`]
     //
     fn select_random(self, conf: SEL, rng: &'rng mut R) -> SEL::Output<'s>;
 }
@@ -0,0 +1,356 @@
+use super::Selector;
+use crate::prelude::RngCoreExtension;
+use core::iter::{Copied, FusedIterator};
+
+#[cfg(not(feature = "std"))]
+use alloc::vec;
+#[cfg(not(feature = "std"))]
+use alloc::vec::{IntoIter, Vec};
+#[cfg(feature = "std")]
+use std::vec::{IntoIter, Vec};
+
+/// Lazy iterator over maybe-overlapping random indices into a slice.
+///
+/// Holds on to the rng and draws each index with [`RngCoreExtension::next_bounded_usize`] as it
+/// is needed, so the iterator never allocates and every index follows the rng's draws.
+#[derive(Debug)]
+pub struct OverlapIndices<'rng, R: ?Sized> {
+    len: usize,
+    remaining: usize,
+    rng: &'rng mut R,
+}
+
+impl<'rng, R: RngCoreExtension + ?Sized> OverlapIndices<'rng, R> {
+    #[inline]
+    const fn new(len: usize, k: usize, rng: &'rng mut R) -> Self {
+        let remaining = if len == 0 { 0 } else { k };
+        Self { len, remaining, rng }
+    }
+}
+
+impl<R: RngCoreExtension + ?Sized> Iterator for OverlapIndices<'_, R> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        self.remaining -= 1;
+        Some(self.rng.next_bounded_usize(self.len))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<R: RngCoreExtension + ?Sized> ExactSizeIterator for OverlapIndices<'_, R> {}
+impl<R: RngCoreExtension + ?Sized> FusedIterator for OverlapIndices<'_, R> {}
+
+/// Iterator over non-overlapping random indices into a slice.
+///
+/// The indices are drawn up front with Floyd's algorithm, which takes `O(k)` time and memory
+/// regardless of the slice's length. Each index is drawn through the selector's `f`, redrawing
+/// the rare values that would bias it. The order of the indices is unspecified.
+#[derive(Clone, Debug)]
+pub struct NonoverlapIndices {
+    indices: IntoIter<usize>,
+}
+
+impl NonoverlapIndices {
+    #[inline]
+    fn new<R: ?Sized>(len: usize, k: usize, rng: &mut R, mut f: impl FnMut(&mut R) -> usize) -> Self {
+        let mut indices = Vec::new();
+        if len == 0 || k == 0 || k > len {
+            // cannot select more items than available non-overlapping
+            // API design: nonpanicking, return an empty iterator instead.
+            return Self {
+                indices: indices.into_iter(),
+            };
+        }
+
+        indices.reserve_exact(k);
+        let mut seen = IndexSet::with_capacity(k);
+        for j in (len - k)..len {
+            let t = bounded(rng, &mut f, j + 1);
+            // `j` itself can never have been picked before, so it's always a valid replacement
+            let pick = if seen.insert(t) {
+                t
+            } else {
+                seen.insert(j);
+                j
+            };
+            indices.push(pick);
+        }
+
+        Self {
+            indices: indices.into_iter(),
+        }
+    }
+}
+
+impl Iterator for NonoverlapIndices {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.indices.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl ExactSizeIterator for NonoverlapIndices {}
+impl FusedIterator for NonoverlapIndices {}
+
+/// A value in `0..bound` from `f`, without modulo bias.
+///
+/// Rejects the lowest `2^BITS % bound` values so that the rest wrap around `bound` evenly.
+#[inline]
+fn bounded<R: ?Sized>(rng: &mut R, f: &mut impl FnMut(&mut R) -> usize, bound: usize) -> usize {
+    let threshold = bound.wrapping_neg() % bound;
+    loop {
+        let x = f(rng);
+        if x >= threshold {
+            return x % bound;
+        }
+    }
+}
+
+/// Iterator that maps random indices to references into a slice.
+#[derive(Clone, Debug)]
+pub struct SelectedIter<'s, T, I> {
+    slice: &'s [T],
+    indices: I,
+}
+
+impl<'s, T, I> Iterator for SelectedIter<'s, T, I>
+where
+    I: Iterator<Item = usize>,
+{
+    type Item = &'s T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let slice = self.slice;
+        self.indices.find_map(|idx| slice.get(idx))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl<T, I> ExactSizeIterator for SelectedIter<'_, T, I> where I: ExactSizeIterator<Item = usize> {}
+impl<T, I> FusedIterator for SelectedIter<'_, T, I> where I: FusedIterator<Item = usize> {}
+
+/// Small open-addressing hash set for Floyd's algorithm, so membership checks stay `O(1)`.
+struct IndexSet {
+    // stores `index + 1`, zero marks an empty slot
+    slots: Vec<usize>,
+    mask: usize,
+}
+
+impl IndexSet {
+    #[inline]
+    fn with_capacity(capacity: usize) -> Self {
+        let size = (capacity * 2).next_power_of_two();
+        Self {
+            slots: vec![0; size],
+            mask: size - 1,
+        }
+    }
+
+    /// Returns `true` if the index wasn't already in the set.
+    #[inline]
+    fn insert(&mut self, index: usize) -> bool {
+        let mut slot = (index as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15).rotate_left(32) as usize & self.mask;
+        loop {
+            match self.slots[slot] {
+                0 => {
+                    self.slots[slot] = index + 1;
+                    return true;
+                }
+                stored if stored == index + 1 => return false,
+                _ => slot = (slot + 1) & self.mask,
+            }
+        }
+    }
+}
+
+macro_rules! iter_selector {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $item:literal, $output:ty $(where T: $bound:path)?, $(R: $rng_bound:path,)?
+        |$input:ident, $k:ident, $rng:ident, $f:pat_param| $wrap:expr
+    ) => {
+        $(#[$meta])*
+        #[doc = ""]
+        #[doc = "#### Input: `&[T]`"]
+        #[doc = ::core::concat!("#### Output: `impl Iterator<Item = ", $item, ">`")]
+        #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+        pub struct $name(pub usize);
+
+        impl<'rng, R: ?Sized $(+ $rng_bound)? + 'rng, T $(: $bound)?> Selector<'rng, T, R> for $name {
+            type Input<'s>
+                = &'s [T]
+            where
+                T: 's;
+            type Output<'s>
+                = $output
+            where
+                T: 's;
+
+            #[inline]
+            fn run<'s>(self, $input: Self::Input<'s>, $rng: &'rng mut R, $f: impl FnMut(&mut R) -> usize) -> Self::Output<'s> {
+                let $k = self.0;
+                $wrap
+            }
+        }
+    };
+}
+
+iter_selector!(
+    /// Configuration for lazily selecting multiple maybe-overlapping immutable items from a given slice.
+    ///
+    /// See [`OverlapIndices`] for how the randomness is drawn, the `f` of
+    /// [`select_random_with_rng`](super::select_random_with_rng) isn't used.
+    ///
+    /// - If the slice is empty, an empty iterator is returned.
+    MultiSelectorImmutOverlapIter, "&T", SelectedIter<'s, T, OverlapIndices<'rng, R>>, R: RngCoreExtension,
+    |input, k, rng, _| SelectedIter { slice: input, indices: OverlapIndices::new(input.len(), k, rng) }
+);
+
+iter_selector!(
+    /// Configuration for lazily selecting multiple maybe-overlapping items by value from a given slice.
+    ///
+    /// See [`OverlapIndices`] for how the randomness is drawn, the `f` of
+    /// [`select_random_with_rng`](super::select_random_with_rng) isn't used.
+    ///
+    /// - If the slice is empty, an empty iterator is returned.
+    MultiSelectorCopiedOverlapIter, "T", Copied<SelectedIter<'s, T, OverlapIndices<'rng, R>>> where T: Copy, R: RngCoreExtension,
+    |input, k, rng, _| SelectedIter { slice: input, indices: OverlapIndices::new(input.len(), k, rng) }.copied()
+);
+
+iter_selector!(
+    /// Configuration for lazily selecting multiple maybe-overlapping indices into a given slice.
+    ///
+    /// See [`OverlapIndices`] for how the randomness is drawn, the `f` of
+    /// [`select_random_with_rng`](super::select_random_with_rng) isn't used.
+    ///
+    /// - If the slice is empty, an empty iterator is returned.
+    MultiSelectorIndexOverlapIter, "usize", OverlapIndices<'rng, R>, R: RngCoreExtension,
+    |input, k, rng, _| OverlapIndices::new(input.len(), k, rng)
+);
+iter_selector!(
+    /// Configuration for selecting multiple non-overlapping immutable items from a given slice
+    /// as an iterator, using Floyd's algorithm.
+    ///
+    /// See [`NonoverlapIndices`] for how the randomness is drawn.
+    ///
+    /// - If the slice is empty, `k == 0`, or if the requested size `k` is bigger than the slice's length,
+    ///   an empty iterator is returned.
+    MultiSelectorImmutNonoverlapIter, "&T", SelectedIter<'s, T, NonoverlapIndices>,
+    |input, k, rng, f| SelectedIter { slice: input, indices: NonoverlapIndices::new(input.len(), k, rng, f) }
+);
+
+iter_selector!(
+    /// Configuration for selecting multiple non-overlapping items by value from a given slice
+    /// as an iterator, using Floyd's algorithm.
+    ///
+    /// See [`NonoverlapIndices`] for how the randomness is drawn.
+    ///
+    /// - If the slice is empty, `k == 0`, or if the requested size `k` is bigger than the slice's length,
+    ///   an empty iterator is returned.
+    MultiSelectorCopiedNonoverlapIter, "T", Copied<SelectedIter<'s, T, NonoverlapIndices>> where T: Copy,
+    |input, k, rng, f| SelectedIter { slice: input, indices: NonoverlapIndices::new(input.len(), k, rng, f) }.copied()
+);
+
+iter_selector!(
+    /// Configuration for selecting multiple non-overlapping indices into a given slice
+    /// as an iterator, using Floyd's algorithm.
+    ///
+    /// See [`NonoverlapIndices`] for how the randomness is drawn.
+    ///
+    /// - If the slice is empty, `k == 0`, or if the requested size `k` is bigger than the slice's length,
+    ///   an empty iterator is returned.
+    MultiSelectorIndexNonoverlapIter, "usize", NonoverlapIndices,
+    |input, k, rng, f| NonoverlapIndices::new(input.len(), k, rng, f)
+);
+
+#[cfg(test)]
+mod tests {
+    use super::{bounded, MultiSelectorCopiedOverlapIter, MultiSelectorIndexNonoverlapIter, MultiSelectorIndexOverlapIter, Vec};
+    use crate::prelude::RngCoreExtension;
+    use crate::rng::XorShift128;
+    use crate::slice_methods::{select_random, select_random_with_rng};
+
+    const SLICE: [u32; 10] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+    #[test]
+    fn nonoverlap_indices_are_distinct_and_in_range() {
+        let mut rng = XorShift128::new(1);
+        for k in 0..=SLICE.len() {
+            let mut indices: Vec<usize> = select_random(MultiSelectorIndexNonoverlapIter(k), &SLICE[..], &mut rng).collect();
+            assert_eq!(indices.len(), k, "every requested index should be returned");
+            indices.sort_unstable();
+            indices.dedup();
+            assert_eq!(indices.len(), k, "the indices should be distinct");
+            assert!(indices.iter().all(|&i| i < SLICE.len()), "the indices should be in range");
+        }
+    }
+
+    #[test]
+    fn nonoverlap_of_the_whole_slice_is_a_permutation() {
+        let mut rng = XorShift128::new(2);
+        let mut indices: Vec<usize> = select_random(MultiSelectorIndexNonoverlapIter(SLICE.len()), &SLICE[..], &mut rng).collect();
+        indices.sort_unstable();
+        assert_eq!(indices, (0..SLICE.len()).collect::<Vec<_>>(), "`k == len` should pick every index once");
+
+        let too_many = select_random(MultiSelectorIndexNonoverlapIter(SLICE.len() + 1), &SLICE[..], &mut rng);
+        assert_eq!(too_many.count(), 0, "`k > len` should pick nothing");
+    }
+
+    #[test]
+    fn overlap_indices_are_in_range_and_follow_the_rng() {
+        let mut rng = XorShift128::new(3);
+        let mut expected = XorShift128::new(3);
+        let indices = select_random(MultiSelectorIndexOverlapIter(100), &SLICE[..], &mut rng);
+        assert_eq!(indices.len(), 100, "every requested index should be returned");
+        for index in indices {
+            assert!(index < SLICE.len(), "the indices should be in range");
+            assert_eq!(index, expected.next_bounded_usize(SLICE.len()), "each index should be one bounded draw");
+        }
+
+        let values: Vec<u32> = select_random(MultiSelectorCopiedOverlapIter(SLICE.len()), &SLICE[..], &mut rng).collect();
+        assert_eq!(values.len(), SLICE.len(), "`k == len` should return `len` items");
+    }
+
+    #[test]
+    fn zero_items_draw_nothing() {
+        let mut rng = XorShift128::new(4);
+        let before = rng.get_current_state();
+        assert_eq!(select_random(MultiSelectorIndexOverlapIter(0), &SLICE[..], &mut rng).count(), 0);
+        assert_eq!(select_random(MultiSelectorIndexNonoverlapIter(0), &SLICE[..], &mut rng).count(), 0);
+        assert_eq!(select_random(MultiSelectorIndexOverlapIter(3), &[] as &[u32], &mut rng).count(), 0);
+        assert_eq!(rng.get_current_state(), before, "selecting nothing shouldn't draw from the rng");
+    }
+
+    #[test]
+    fn bounded_rejects_the_biased_values() {
+        // 2^BITS % 3 == 1 for both 32 and 64 bit `usize`, so only 0 is rejected
+        let mut draws = [0, usize::MAX].into_iter();
+        assert_eq!(bounded(&mut (), &mut |_: &mut ()| draws.next().unwrap(), 3), usize::MAX % 3);
+
+        let mut rng = XorShift128::new(5);
+        let picked: Vec<usize> = select_random_with_rng(MultiSelectorIndexNonoverlapIter(1), &SLICE[..8], &mut rng, |_| 0).collect();
+        assert_eq!(picked, [0], "a draw of zero is accepted for a bound that divides 2^BITS");
+    }
+}
@@ -1,16 +1,47 @@
 use crate::prelude::RngCoreExtension;
+use core::iter::FusedIterator;
+use core::mem;
 
 /// trait for things that can be shuffled around
 pub trait Shuffle {
     /// Shuffle the given slice using the given RNG. After this operation,
     /// nothing can be said about the ordering of items inside the slice.
-    /// 
+    ///
     /// This will never panic.
     fn shuffle_with<R>(&mut self, rng: &mut R) -> &mut Self
     where
         R: RngCoreExtension + ?Sized;
 }
 
+/// Shuffling methods that only walk part of the slice.
+///
+/// These live apart from [`Shuffle`] so that implementing it only takes `shuffle_with`.
+pub trait ShuffleExt: Shuffle {
+    /// Shuffle only the first `amount` items into place, as if the whole slice was
+    /// shuffled and then split. Returns `(shuffled, rest)`, where `shuffled` holds
+    /// `amount` items picked uniformly at random in a random order, and `rest` holds
+    /// the remaining items in an unspecified order.
+    ///
+    /// Only `amount` bounded draws are made, so dealing 5 cards from a deck of 52
+    /// doesn't pay for shuffling the other 47.
+    ///
+    /// If `amount` is bigger than the slice's length, the whole slice is shuffled.
+    /// This will never panic.
+    fn partial_shuffle<R>(&mut self, amount: usize, rng: &mut R) -> (&mut Self, &mut Self)
+    where
+        R: RngCoreExtension + ?Sized;
+
+    /// Iterate over the items in a random order, doing the Fisher-Yates shuffle lazily.
+    ///
+    /// Each call to `next` makes a single bounded draw and swaps the picked item into
+    /// place, so stopping early leaves the rest of the slice mostly untouched.
+    ///
+    /// This will never panic.
+    fn shuffled_iter<'a, 'r, R>(&'a mut self, rng: &'r mut R) -> ShuffledIter<'a, 'r, Self, R>
+    where
+        R: RngCoreExtension + ?Sized;
+}
+
 impl<T> Shuffle for [T] {
     #[inline]
     fn shuffle_with<R>(&mut self, rng: &mut R) -> &mut Self
@@ -25,3 +56,71 @@ impl<T> Shuffle for [T] {
         self
     }
 }
+
+impl<T> ShuffleExt for [T] {
+    #[inline]
+    fn partial_shuffle<R>(&mut self, amount: usize, rng: &mut R) -> (&mut Self, &mut Self)
+    where
+        R: RngCoreExtension + ?Sized,
+    {
+        let len = self.len();
+        let amount = amount.min(len);
+        for i in 0..amount {
+            let j = i + rng.next_bounded_usize(len - i);
+            self.swap(i, j);
+        }
+
+        self.split_at_mut(amount)
+    }
+
+    #[inline]
+    fn shuffled_iter<'a, 'r, R>(&'a mut self, rng: &'r mut R) -> ShuffledIter<'a, 'r, Self, R>
+    where
+        R: RngCoreExtension + ?Sized,
+    {
+        ShuffledIter { rest: self, rng }
+    }
+}
+
+/// Iterator over the items of a slice in a random order, see [`ShuffleExt::shuffled_iter`].
+///
+/// Yields mutable references, the slice ends up permuted in the order the items were yielded.
+#[derive(Debug)]
+pub struct ShuffledIter<'a, 'r, S, R>
+where
+    S: ?Sized,
+    R: ?Sized,
+{
+    rest: &'a mut S,
+    rng: &'r mut R,
+}
+
+impl<'a, T, R> Iterator for ShuffledIter<'a, '_, [T], R>
+where
+    R: RngCoreExtension + ?Sized,
+{
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = mem::take(&mut self.rest);
+        if rest.is_empty() {
+            return None;
+        }
+
+        let j = self.rng.next_bounded_usize(rest.len());
+        rest.swap(0, j);
+        let (first, rest) = rest.split_first_mut()?;
+        self.rest = rest;
+
+        Some(first)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.rest.len(), Some(self.rest.len()))
+    }
+}
+
+impl<T, R> ExactSizeIterator for ShuffledIter<'_, '_, [T], R> where R: RngCoreExtension + ?Sized {}
+impl<T, R> FusedIterator for ShuffledIter<'_, '_, [T], R> where R: RngCoreExtension + ?Sized {}
@@ -0,0 +1,421 @@
+use super::Selector;
+use crate::prelude::RngCoreExtension;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// A weighted sampler backed by a sum tree.
+///
+/// Unlike an alias table, the weights can be changed after construction without
+/// rebuilding anything: [`WeightedTree::update`], [`WeightedTree::sample`] and
+/// [`WeightedTree::sample_remove`] are all `O(log n)`.
+///
+/// Negative, NaN and infinite weights are treated as zero. Items with a weight of
+/// zero are never sampled.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeightedTree {
+    len: usize,
+    leaves: usize,
+    // implicit binary tree: node `i` has children `2i` and `2i + 1`, the root is
+    // at index 1 and the leaves start at index `leaves`.
+    tree: Vec<f64>,
+}
+
+impl WeightedTree {
+    /// Create a new tree with the given weights.
+    #[inline]
+    #[must_use]
+    pub fn new(weights: &[f64]) -> Self {
+        let mut this = Self::zeroed(weights.len());
+        for (leaf, &weight) in this.tree[this.leaves..].iter_mut().zip(weights) {
+            *leaf = sanitize_weight(weight);
+        }
+        this.rebuild();
+        this
+    }
+
+    /// Create a new tree with `len` items that all have a weight of zero.
+    #[inline]
+    #[must_use]
+    pub fn zeroed(len: usize) -> Self {
+        let leaves = len.next_power_of_two();
+        Self {
+            len,
+            leaves,
+            tree: vec![0.0; leaves * 2],
+        }
+    }
+
+    /// Number of items in the tree, including the ones with a weight of zero.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the tree has no items.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Sum of all the weights in the tree.
+    #[inline]
+    #[must_use]
+    pub fn total(&self) -> f64 {
+        self.tree[1]
+    }
+
+    /// Get the weight of the item at `index`, or `None` if it is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn weight(&self, index: usize) -> Option<f64> {
+        (index < self.len).then(|| self.tree[self.leaves + index])
+    }
+
+    /// Change the weight of the item at `index`, returning the previous weight.
+    ///
+    /// Returns `None` and does nothing if `index` is out of bounds.
+    #[inline]
+    pub fn update(&mut self, index: usize, weight: f64) -> Option<f64> {
+        if index >= self.len {
+            return None;
+        }
+
+        let mut node = self.leaves + index;
+        let previous = self.tree[node];
+        self.tree[node] = sanitize_weight(weight);
+
+        while node > 1 {
+            node /= 2;
+            self.tree[node] = self.tree[node * 2] + self.tree[node * 2 + 1];
+        }
+
+        Some(previous)
+    }
+
+    /// Set the weight of the item at `index` to zero so it can no longer be sampled,
+    /// returning the previous weight.
+    ///
+    /// Returns `None` and does nothing if `index` is out of bounds.
+    #[inline]
+    pub fn remove(&mut self, index: usize) -> Option<f64> {
+        self.update(index, 0.0)
+    }
+
+    /// Append a new item with the given weight to the end of the tree.
+    ///
+    /// This is amortized `O(log n)`, the tree doubles its capacity when it runs out of room.
+    #[inline]
+    pub fn push(&mut self, weight: f64) {
+        if self.len == self.leaves {
+            let mut grown = Self::zeroed(self.leaves * 2);
+            grown.len = self.len;
+            grown.tree[grown.leaves..grown.leaves + self.len].copy_from_slice(&self.tree[self.leaves..self.leaves + self.len]);
+            grown.rebuild();
+            *self = grown;
+        }
+
+        self.len += 1;
+        self.update(self.len - 1, weight);
+    }
+
+    /// Sample the index of an item with a probability proportional to its weight.
+    ///
+    /// Returns `None` if the tree is empty or all the weights are zero.
+    #[inline]
+    pub fn sample<R>(&self, rng: &mut R) -> Option<usize>
+    where
+        R: RngCoreExtension + ?Sized,
+    {
+        self.sample_with(rng, R::next_usize)
+    }
+
+    /// Sample the index of an item with a probability proportional to its weight.
+    ///
+    /// `f` should be a function that takes in the given `&mut R` and spits out a random `usize`.
+    ///
+    /// Returns `None` if the tree is empty or all the weights are zero.
+    #[inline]
+    pub fn sample_with<R>(&self, rng: &mut R, mut f: impl FnMut(&mut R) -> usize) -> Option<usize>
+    where
+        R: ?Sized,
+    {
+        let total = self.total();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut target = unit_f64_from_usize(f(rng)) * total;
+        let mut node = 1;
+        while node < self.leaves {
+            let left = node * 2;
+            let left_weight = self.tree[left];
+            let right_weight = self.tree[left + 1];
+
+            // floating point rounding can push `target` past the last nonzero subtree,
+            // never walk into a subtree that has nothing to sample.
+            if (target < left_weight && left_weight > 0.0) || right_weight <= 0.0 {
+                node = left;
+            } else {
+                target -= left_weight;
+                node = left + 1;
+            }
+        }
+
+        Some(node - self.leaves)
+    }
+
+    /// Sample the index of an item with a probability proportional to its weight, then
+    /// remove it from the tree so it won't be sampled again.
+    ///
+    /// Returns `None` if the tree is empty or all the weights are zero.
+    #[inline]
+    pub fn sample_remove<R>(&mut self, rng: &mut R) -> Option<usize>
+    where
+        R: RngCoreExtension + ?Sized,
+    {
+        let index = self.sample(rng)?;
+        self.remove(index);
+        Some(index)
+    }
+
+    #[inline]
+    fn rebuild(&mut self) {
+        for node in (1..self.leaves).rev() {
+            self.tree[node] = self.tree[node * 2] + self.tree[node * 2 + 1];
+        }
+    }
+}
+
+impl FromIterator<f64> for WeightedTree {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        let weights: Vec<f64> = iter.into_iter().collect();
+        Self::new(&weights)
+    }
+}
+
+#[inline]
+const fn sanitize_weight(weight: f64) -> f64 {
+    if weight.is_finite() && weight > 0.0 {
+        weight
+    } else {
+        0.0
+    }
+}
+
+/// Map a random `usize` to a float in `[0, 1)`, keeping as many bits as an `f64` can hold.
+#[inline]
+fn unit_f64_from_usize(value: usize) -> f64 {
+    let shift = usize::BITS.saturating_sub(f64::MANTISSA_DIGITS);
+    let bits = usize::BITS - shift;
+    (value >> shift) as f64 / (1_u64 << bits) as f64
+}
+
+/// Configuration for selecting a single immutable item from a given slice, weighted by a [`WeightedTree`].
+///
+/// The weight of `slice[i]` is the weight of index `i` in the tree.
+///
+/// #### Input: `&[T]`
+/// #### Output: `Option<&T>`
+///
+/// - If the slice is empty, all the weights are zero, or the sampled index is out of the slice's bounds,
+///   `None` is returned.
+#[derive(Debug, Clone, Copy)]
+pub struct WeightedSelectorOneImmut<'w>(pub &'w WeightedTree);
+
+impl<'rng, R: ?Sized + 'rng, T> Selector<'rng, T, R> for WeightedSelectorOneImmut<'_> {
+    type Input<'s>
+        = &'s [T]
+    where
+        T: 's;
+    type Output<'s>
+        = Option<&'s T>
+    where
+        T: 's;
+
+    #[inline]
+    fn run<'s>(self, input: Self::Input<'s>, rng: &'rng mut R, f: impl FnMut(&mut R) -> usize) -> Self::Output<'s> {
+        let idx = self.0.sample_with(rng, f)?;
+        input.get(idx)
+    }
+}
+
+/// Configuration for selecting multiple maybe-overlapping immutable items from a given slice,
+/// weighted by a [`WeightedTree`].
+///
+/// The weight of `slice[i]` is the weight of index `i` in the tree.
+///
+/// #### Input: `&[T]`
+/// #### Output: `Vec<&T>`
+///
+/// - If the slice is empty or all the weights are zero, `vec![]` is returned.
+/// - Sampled indices that are out of the slice's bounds are skipped.
+#[derive(Debug, Clone, Copy)]
+pub struct WeightedMultiSelectorImmutOverlap<'w>(pub &'w WeightedTree, pub usize);
+
+impl<'rng, R: ?Sized + 'rng, T> Selector<'rng, T, R> for WeightedMultiSelectorImmutOverlap<'_> {
+    type Input<'s>
+        = &'s [T]
+    where
+        T: 's;
+    type Output<'s>
+        = Vec<&'s T>
+    where
+        T: 's;
+
+    #[inline]
+    fn run<'s>(self, input: Self::Input<'s>, rng: &'rng mut R, mut f: impl FnMut(&mut R) -> usize) -> Self::Output<'s> {
+        let mut buf = vec![];
+        if input.is_empty() || self.0.total() <= 0.0 {
+            return buf;
+        }
+
+        buf.reserve_exact(self.1);
+        for _ in 0..self.1 {
+            if let Some(item) = self.0.sample_with(rng, &mut f).and_then(|idx| input.get(idx)) {
+                buf.push(item);
+            }
+        }
+
+        buf
+    }
+}
+
+/// Configuration for selecting multiple non-overlapping immutable items from a given slice,
+/// weighted by a [`WeightedTree`].
+///
+/// The weight of `slice[i]` is the weight of index `i` in the tree. Items are removed from the
+/// tree as they are sampled and their weights are restored before returning, so the tree is
+/// left unchanged.
+///
+/// #### Input: `&[T]`
+/// #### Output: `Vec<&T>`
+///
+/// - If the slice is empty or all the weights are zero, `vec![]` is returned.
+/// - If there are fewer than `k` items with a nonzero weight, all of them are returned.
+/// - Sampled indices that are out of the slice's bounds are skipped.
+#[derive(Debug)]
+pub struct WeightedMultiSelectorImmutNonoverlap<'w>(pub &'w mut WeightedTree, pub usize);
+
+impl<'rng, R: ?Sized + 'rng, T> Selector<'rng, T, R> for WeightedMultiSelectorImmutNonoverlap<'_> {
+    type Input<'s>
+        = &'s [T]
+    where
+        T: 's;
+    type Output<'s>
+        = Vec<&'s T>
+    where
+        T: 's;
+
+    #[inline]
+    fn run<'s>(self, input: Self::Input<'s>, rng: &'rng mut R, mut f: impl FnMut(&mut R) -> usize) -> Self::Output<'s> {
+        let Self(tree, k) = self;
+        let mut buf = vec![];
+        if input.is_empty() || tree.total() <= 0.0 {
+            return buf;
+        }
+
+        let mut removed = Vec::with_capacity(k);
+        for _ in 0..k {
+            let Some(idx) = tree.sample_with(rng, &mut f) else {
+                break;
+            };
+            if let Some(weight) = tree.remove(idx) {
+                removed.push((idx, weight));
+            }
+            if let Some(item) = input.get(idx) {
+                buf.push(item);
+            }
+        }
+
+        for (idx, weight) in removed {
+            tree.update(idx, weight);
+        }
+
+        buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Vec, WeightedTree};
+    use crate::rng::XorShift128;
+    use core::iter;
+
+    /// draws that land on the very start, the middle and the very end of the total weight
+    const DRAWS: [usize; 5] = [0, 1, usize::MAX / 3, usize::MAX / 2, usize::MAX];
+
+    fn samples(tree: &WeightedTree) -> Vec<Option<usize>> {
+        DRAWS.iter().map(|&draw| tree.sample_with(&mut (), |_| draw)).collect()
+    }
+
+    #[test]
+    fn zero_weights_are_never_sampled() {
+        let tree = WeightedTree::new(&[0.0, 2.0, 0.0, -1.0, f64::NAN, f64::INFINITY]);
+        assert_eq!(tree.total(), 2.0);
+        assert_eq!(tree.weight(3), Some(0.0));
+        assert!(samples(&tree).iter().all(|&index| index == Some(1)));
+    }
+
+    #[test]
+    fn all_zero_weights_sample_nothing() {
+        assert_eq!(samples(&WeightedTree::new(&[0.0; 5])), [None; 5]);
+        assert_eq!(samples(&WeightedTree::zeroed(0)), [None; 5]);
+        assert_eq!(samples(&WeightedTree::new(&[])), [None; 5]);
+    }
+
+    #[test]
+    fn single_item() {
+        let mut tree = WeightedTree::new(&[3.0]);
+        assert_eq!(tree.len(), 1);
+        assert_eq!(samples(&tree), [Some(0); 5]);
+
+        assert_eq!(tree.remove(0), Some(3.0));
+        assert_eq!(samples(&tree), [None; 5]);
+
+        tree.push(1.0);
+        assert_eq!(tree.len(), 2);
+        assert_eq!(samples(&tree), [Some(1); 5]);
+    }
+
+    #[test]
+    fn remove_last_item() {
+        let mut tree = WeightedTree::new(&[1.0, 2.0, 3.0]);
+        assert_eq!(tree.remove(2), Some(3.0));
+        assert_eq!(tree.remove(3), None);
+        assert_eq!(tree.total(), 3.0);
+        assert!(samples(&tree).iter().all(|&index| matches!(index, Some(0 | 1))));
+
+        assert_eq!(tree.remove(1), Some(2.0));
+        assert_eq!(tree.remove(0), Some(1.0));
+        assert_eq!(tree.total(), 0.0);
+        assert_eq!(samples(&tree), [None; 5]);
+    }
+
+    #[test]
+    fn update_keeps_sums() {
+        let mut tree = WeightedTree::new(&[1.0, 1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(tree.update(4, 5.0), Some(1.0));
+        assert_eq!(tree.update(5, 5.0), None);
+        assert_eq!(tree.total(), 9.0);
+        assert_eq!(tree.sample_with(&mut (), |_| usize::MAX), Some(4));
+        assert_eq!(tree.sample_with(&mut (), |_| 0), Some(0));
+    }
+
+    #[test]
+    fn sample_remove_drains_every_nonzero_item_once() {
+        let mut rng = XorShift128::new(1);
+        let mut tree = WeightedTree::new(&[1.0, 0.0, 4.0, 2.0, 0.5, 0.0, 8.0]);
+        let mut drawn: Vec<usize> = iter::from_fn(|| tree.sample_remove(&mut rng)).collect();
+        drawn.sort_unstable();
+        assert_eq!(drawn, [0, 2, 3, 4, 6]);
+        assert_eq!(tree.total(), 0.0);
+    }
+}
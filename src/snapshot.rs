@@ -0,0 +1,288 @@
+use crate::error::RngError;
+use crate::rng::pcg_advanced::pcg_128::PcgInnerStateSetseq128;
+use crate::rng::pcg_advanced::pcg_16::PcgInnerStateSetseq16;
+use crate::rng::pcg_advanced::pcg_32::PcgInnerStateSetseq32;
+use crate::rng::pcg_advanced::pcg_64::PcgInnerStateSetseq64;
+use crate::rng::pcg_advanced::pcg_8::PcgInnerStateSetseq8;
+use crate::rng::{Pcg128, Pcg16, Pcg32, Pcg64, Pcg8, SplitMix64, XoRoShiRo128, XorShift128, XorShift32};
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// The version of the snapshot format written by [`Snapshot::to_bytes`].
+pub const FORMAT_VERSION: u8 = 1;
+
+/// Identifies the engine a snapshot was taken from, stored in its first byte.
+///
+/// The values are part of the format and never change.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+#[non_exhaustive]
+pub enum EngineTag {
+    /// [`SplitMix64`]
+    SplitMix64 = 1,
+    /// [`XorShift32`]
+    XorShift32 = 2,
+    /// [`XorShift128`]
+    XorShift128 = 3,
+    /// [`XoRoShiRo128`]
+    XoRoShiRo128 = 4,
+    /// [`Pcg8`]
+    Pcg8 = 5,
+    /// [`Pcg16`]
+    Pcg16 = 6,
+    /// [`Pcg32`]
+    Pcg32 = 7,
+    /// [`Pcg64`]
+    Pcg64 = 8,
+    /// [`Pcg128`]
+    Pcg128 = 9,
+    /// [`PcgInnerStateSetseq8`]
+    PcgInnerStateSetseq8 = 10,
+    /// [`PcgInnerStateSetseq16`]
+    PcgInnerStateSetseq16 = 11,
+    /// [`PcgInnerStateSetseq32`]
+    PcgInnerStateSetseq32 = 12,
+    /// [`PcgInnerStateSetseq64`]
+    PcgInnerStateSetseq64 = 13,
+    /// [`PcgInnerStateSetseq128`]
+    PcgInnerStateSetseq128 = 14,
+}
+
+impl EngineTag {
+    /// Read the tag of a snapshot without decoding the rest of it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RngError::InvalidSnapshot`] if `bytes` is empty or starts with an unknown tag.
+    #[inline]
+    pub fn of(bytes: &[u8]) -> Result<Self, RngError> {
+        let Some(&tag) = bytes.first() else {
+            return Err(RngError::InvalidSnapshot("the snapshot is empty"));
+        };
+        Self::try_from(tag)
+    }
+}
+
+impl TryFrom<u8> for EngineTag {
+    type Error = RngError;
+
+    #[inline]
+    fn try_from(tag: u8) -> Result<Self, Self::Error> {
+        Ok(match tag {
+            1 => Self::SplitMix64,
+            2 => Self::XorShift32,
+            3 => Self::XorShift128,
+            4 => Self::XoRoShiRo128,
+            5 => Self::Pcg8,
+            6 => Self::Pcg16,
+            7 => Self::Pcg32,
+            8 => Self::Pcg64,
+            9 => Self::Pcg128,
+            10 => Self::PcgInnerStateSetseq8,
+            11 => Self::PcgInnerStateSetseq16,
+            12 => Self::PcgInnerStateSetseq32,
+            13 => Self::PcgInnerStateSetseq64,
+            14 => Self::PcgInnerStateSetseq128,
+            _ => return Err(RngError::InvalidSnapshot("unknown engine tag")),
+        })
+    }
+}
+
+impl From<EngineTag> for u8 {
+    #[inline]
+    fn from(tag: EngineTag) -> Self {
+        tag as Self
+    }
+}
+
+/// A compact, versioned binary encoding of an engine's state, for save files.
+///
+/// A snapshot is laid out as:
+///
+/// - 1 byte: the [`EngineTag`]
+/// - 1 byte: the format version, currently [`FORMAT_VERSION`]
+/// - the engine's state words, each in little-endian byte order
+///
+/// Every word has a fixed width, so a snapshot taken on any platform restores the same
+/// engine on every other platform, whatever its pointer width or endianness.
+/// [`AnyRng::from_bytes`](crate::rng::AnyRng::from_bytes) restores a snapshot without knowing
+/// its engine in advance.
+///
+/// ```rust
+/// use voxell_rng::prelude::*;
+/// use voxell_rng::rng::XoRoShiRo128;
+/// use voxell_rng::snapshot::Snapshot;
+///
+/// let mut rng = XoRoShiRo128::new(42);
+/// let save = rng.to_bytes();
+///
+/// let mut restored = XoRoShiRo128::from_bytes(&save).unwrap();
+/// assert_eq!(rng.next_u64(), restored.next_u64());
+/// ```
+pub trait Snapshot: Sized {
+    /// The tag identifying this engine in a snapshot.
+    const TAG: EngineTag;
+
+    /// Encode the current state of the engine.
+    #[must_use]
+    fn to_bytes(&self) -> Vec<u8>;
+
+    /// Restore an engine from a snapshot made by [`Snapshot::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RngError::InvalidSnapshot`] if the snapshot is for another engine, has an
+    /// unsupported version or the wrong length, and [`RngError::InvalidSeed`] if the state
+    /// isn't one the engine can run from.
+    fn from_bytes(bytes: &[u8]) -> Result<Self, RngError>;
+}
+
+/// A fixed-width state word.
+trait Word: Copy + Default {
+    const BYTES: usize;
+
+    fn write_le(self, out: &mut Vec<u8>);
+
+    /// `bytes` is exactly `BYTES` long.
+    fn read_le(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_word {
+    ($($word:ty),*) => {
+        $(
+            impl Word for $word {
+                const BYTES: usize = core::mem::size_of::<$word>();
+
+                #[inline]
+                fn write_le(self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+
+                #[inline]
+                fn read_le(bytes: &[u8]) -> Self {
+                    let mut le = [0; core::mem::size_of::<$word>()];
+                    le.copy_from_slice(bytes);
+                    Self::from_le_bytes(le)
+                }
+            }
+        )*
+    };
+}
+
+impl_word!(u8, u16, u32, u64, u128);
+
+#[inline]
+fn encode<W: Word, const N: usize>(tag: EngineTag, words: [W; N]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(2 + N * W::BYTES);
+    bytes.push(tag.into());
+    bytes.push(FORMAT_VERSION);
+    for word in words {
+        word.write_le(&mut bytes);
+    }
+    bytes
+}
+
+#[inline]
+fn decode<W: Word, const N: usize>(tag: EngineTag, bytes: &[u8]) -> Result<[W; N], RngError> {
+    if EngineTag::of(bytes)? != tag {
+        return Err(RngError::InvalidSnapshot("the snapshot is for a different engine"));
+    }
+
+    let Some((&version, state)) = bytes.get(1..).and_then(<[u8]>::split_first) else {
+        return Err(RngError::InvalidSnapshot("the snapshot is missing its version"));
+    };
+    if version != FORMAT_VERSION {
+        return Err(RngError::InvalidSnapshot("unsupported snapshot version"));
+    }
+    if state.len() != N * W::BYTES {
+        return Err(RngError::InvalidSnapshot("the snapshot has the wrong length for its engine"));
+    }
+
+    let mut words = [W::default(); N];
+    for (word, le) in words.iter_mut().zip(state.chunks_exact(W::BYTES)) {
+        *word = W::read_le(le);
+    }
+    Ok(words)
+}
+
+macro_rules! impl_snapshot {
+    ($($engine:ident: [$word:ty; $n:literal] => |$rng:ident| $to:expr, |$words:pat_param| $from:expr;)*) => {
+        $(
+            impl Snapshot for $engine {
+                const TAG: EngineTag = EngineTag::$engine;
+
+                #[inline]
+                fn to_bytes(&self) -> Vec<u8> {
+                    let $rng = self;
+                    encode::<$word, $n>(Self::TAG, $to)
+                }
+
+                #[inline]
+                fn from_bytes(bytes: &[u8]) -> Result<Self, RngError> {
+                    let $words = decode::<$word, $n>(Self::TAG, bytes)?;
+                    $from
+                }
+            }
+        )*
+    };
+}
+
+impl_snapshot! {
+    SplitMix64: [u64; 1] => |rng| [rng.get_current_state()], |[x]| Ok(Self::wrap(x));
+    XorShift32: [u32; 1] => |rng| [rng.get_current_state()], |[x]| Self::try_wrap(x);
+    XorShift128: [u64; 2] => |rng| rng.get_current_state(), |state| Self::try_wrap(state);
+    XoRoShiRo128: [u64; 2] => |rng| rng.get_current_state(), |state| Self::try_wrap(state);
+    Pcg8: [u8; 1] => |rng| [rng.state_word()], |[state]| Ok(Self::from_state_word(state));
+    Pcg16: [u16; 1] => |rng| [rng.state_word()], |[state]| Ok(Self::from_state_word(state));
+    Pcg32: [u32; 1] => |rng| [rng.state_word()], |[state]| Ok(Self::from_state_word(state));
+    Pcg64: [u64; 1] => |rng| [rng.state_word()], |[state]| Ok(Self::from_state_word(state));
+    Pcg128: [u128; 1] => |rng| [rng.state_word()], |[state]| Ok(Self::from_state_word(state));
+    PcgInnerStateSetseq8: [u8; 2] => |rng| rng.parts(), |parts| Self::try_from(parts);
+    PcgInnerStateSetseq16: [u16; 2] => |rng| rng.parts(), |parts| Self::try_from(parts);
+    PcgInnerStateSetseq32: [u32; 2] => |rng| rng.parts(), |parts| Self::try_from(parts);
+    PcgInnerStateSetseq64: [u64; 2] => |rng| rng.parts(), |parts| Self::try_from(parts);
+    PcgInnerStateSetseq128: [u128; 2] => |rng| rng.parts(), |parts| Self::try_from(parts);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EngineTag, Snapshot, FORMAT_VERSION};
+    use crate::error::RngError;
+    use crate::rng::{AnyRng, XoRoShiRo128, XorShift128};
+    use rand_core::RngCore;
+
+    #[test]
+    fn round_trip_from_a_zero_first_word() {
+        let mut rng = XoRoShiRo128::wrap([0, 0x1234_5678]);
+        let save = rng.to_bytes();
+        assert_eq!(save[..2], [EngineTag::XoRoShiRo128.into(), FORMAT_VERSION]);
+
+        let first = rng.next_u64();
+        let mut restored = XoRoShiRo128::from_bytes(&save).unwrap();
+        assert_eq!(restored.get_current_state(), [0, 0x1234_5678]);
+        assert_eq!(restored.next_u64(), first);
+
+        let mut any = AnyRng::from_bytes(&save).unwrap();
+        assert_eq!(any.next_u64(), first);
+    }
+
+    #[test]
+    fn rejects_broken_snapshots() {
+        let save = XorShift128::new(1).to_bytes();
+        assert!(matches!(XoRoShiRo128::from_bytes(&save), Err(RngError::InvalidSnapshot(_))));
+        assert!(matches!(XorShift128::from_bytes(&save[..10]), Err(RngError::InvalidSnapshot(_))));
+        assert!(matches!(XorShift128::from_bytes(&[3]), Err(RngError::InvalidSnapshot(_))));
+        assert!(matches!(XorShift128::from_bytes(&[]), Err(RngError::InvalidSnapshot(_))));
+
+        let mut newer = save.clone();
+        newer[1] = FORMAT_VERSION + 1;
+        assert!(matches!(XorShift128::from_bytes(&newer), Err(RngError::InvalidSnapshot(_))));
+
+        let mut zeroed = save;
+        zeroed[2..].fill(0);
+        assert!(matches!(XoRoShiRo128::from_bytes(&zeroed), Err(RngError::InvalidSnapshot(_))));
+        zeroed[0] = EngineTag::XoRoShiRo128.into();
+        assert!(matches!(XoRoShiRo128::from_bytes(&zeroed), Err(RngError::InvalidSeed(_))));
+    }
+}
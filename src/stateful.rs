@@ -0,0 +1,162 @@
+use crate::error::RngError;
+use crate::lineage;
+use crate::rng::pcg_advanced::pcg_128::PcgInnerStateSetseq128;
+use crate::rng::pcg_advanced::pcg_16::PcgInnerStateSetseq16;
+use crate::rng::pcg_advanced::pcg_32::PcgInnerStateSetseq32;
+use crate::rng::pcg_advanced::pcg_64::PcgInnerStateSetseq64;
+use crate::rng::pcg_advanced::pcg_8::PcgInnerStateSetseq8;
+use crate::rng::{Pcg128, Pcg16, Pcg32, Pcg64, Pcg8, SplitMix64, XoRoShiRo128, XorShift128, XorShift32};
+use crate::sample::Random;
+use core::array;
+use rand_core::RngCore;
+
+/// Read, overwrite and look ahead in the state of an engine.
+///
+/// Implemented for every engine in [`rng`](crate::rng), so a debugger can inspect any generator
+/// the same way.
+///
+/// ```rust
+/// use voxell_rng::prelude::*;
+/// use voxell_rng::rng::XorShift128;
+///
+/// let mut rng = XorShift128::new(7);
+/// let ahead: [u64; 3] = rng.peek_n();
+/// assert_eq!(rng.peek::<u64>(), ahead[0]);
+///
+/// let saved = rng.state();
+/// let first = [rng.next_u64(), rng.next_u64(), rng.next_u64()];
+/// assert_eq!(first, ahead);
+///
+/// rng.set_state(saved).unwrap();
+/// assert_eq!(rng.next_u64(), first[0]);
+/// ```
+pub trait StatefulRng: RngCore + Sized {
+    /// The words the engine steps from.
+    type State;
+
+    /// The current state of the engine.
+    #[must_use]
+    fn state(&self) -> Self::State;
+
+    /// Create an engine that continues from `state`, as returned by [`StatefulRng::state`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RngError::InvalidSeed`] if the engine can't run from `state`, such as an
+    /// all-zero xorshift state.
+    fn from_state(state: Self::State) -> Result<Self, RngError>;
+
+    /// A copy of the engine that continues from the same state, and draws the same numbers.
+    ///
+    /// Unlike [`Clone`], it is kept with the `strict` feature and not tracked by the
+    /// `clone-lineage` feature. Use [`ForkRng::fork`](crate::branch_rng::ForkRng::fork) for a
+    /// generator that draws different numbers.
+    #[must_use]
+    fn duplicate(&self) -> Self;
+
+    /// Overwrite the state of the engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RngError::InvalidSeed`] if the engine can't run from `state`, such as an
+    /// all-zero xorshift state. The engine is left unchanged in that case.
+    #[inline]
+    fn set_state(&mut self, state: Self::State) -> Result<(), RngError> {
+        *self = Self::from_state(state)?;
+        Ok(())
+    }
+
+    /// The value the next call to [`Random::random`] would generate, without advancing the engine.
+    #[inline]
+    #[must_use]
+    fn peek<T: Random>(&self) -> T {
+        let [value] = self.peek_n();
+        value
+    }
+
+    /// The next `N` values [`Random::random`] would generate, without advancing the engine.
+    ///
+    /// Looks ahead on a copy made with [`StatefulRng::duplicate`], so it works without `Clone`.
+    #[inline]
+    #[must_use]
+    fn peek_n<T: Random, const N: usize>(&self) -> [T; N] {
+        let mut rng = self.duplicate();
+        lineage::untracked(|| array::from_fn(|_| T::random(&mut rng)))
+    }
+}
+
+macro_rules! impl_stateful_rng {
+    (@impl $engine:ty, $state:ty, $get:ident, |$new:ident| $from:expr) => {
+        impl StatefulRng for $engine {
+            type State = $state;
+
+            #[inline]
+            fn state(&self) -> Self::State {
+                self.$get()
+            }
+
+            #[inline]
+            fn from_state($new: Self::State) -> Result<Self, RngError> {
+                $from
+            }
+
+            #[inline]
+            fn duplicate(&self) -> Self {
+                self.untracked_copy()
+            }
+        }
+    };
+    (infallible: $($engine:ty: $state:ty => $get:ident, $ctor:ident;)*) => {
+        $(impl_stateful_rng!(@impl $engine, $state, $get, |state| Ok(Self::$ctor(state)));)*
+    };
+    (fallible: $($engine:ty: $state:ty => $get:ident, $ctor:ident;)*) => {
+        $(impl_stateful_rng!(@impl $engine, $state, $get, |state| Self::$ctor(state));)*
+    };
+}
+
+impl_stateful_rng! {
+    infallible:
+    SplitMix64: u64 => get_current_state, wrap;
+    Pcg8: u8 => state_word, from_state_word;
+    Pcg16: u16 => state_word, from_state_word;
+    Pcg32: u32 => state_word, from_state_word;
+    Pcg64: u64 => state_word, from_state_word;
+    Pcg128: u128 => state_word, from_state_word;
+}
+
+impl_stateful_rng! {
+    fallible:
+    XorShift32: u32 => get_current_state, try_wrap;
+    XorShift128: [u64; 2] => get_current_state, try_wrap;
+    XoRoShiRo128: [u64; 2] => get_current_state, try_wrap;
+    PcgInnerStateSetseq8: [u8; 2] => parts, try_from;
+    PcgInnerStateSetseq16: [u16; 2] => parts, try_from;
+    PcgInnerStateSetseq32: [u32; 2] => parts, try_from;
+    PcgInnerStateSetseq64: [u64; 2] => parts, try_from;
+    PcgInnerStateSetseq128: [u128; 2] => parts, try_from;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StatefulRng;
+    use crate::rng::{XoRoShiRo128, XorShift128};
+    use rand_core::RngCore;
+
+    #[test]
+    fn set_state_accepts_a_zero_first_word() {
+        let rng = XoRoShiRo128::wrap([0, 1]);
+        let mut other = XoRoShiRo128::new(5);
+        other.set_state(rng.state()).unwrap();
+        assert_eq!(other, rng);
+        assert_eq!(other.peek::<u64>(), 1);
+        assert_eq!(other.next_u64(), 1);
+    }
+
+    #[test]
+    fn set_state_keeps_the_engine_on_error() {
+        let mut rng = XorShift128::new(3);
+        let before = rng.state();
+        assert!(rng.set_state([0, 0]).is_err());
+        assert_eq!(rng.state(), before);
+    }
+}
@@ -0,0 +1,142 @@
+use core::convert::Infallible;
+use core::fmt;
+use core::str::FromStr;
+use rand_core::SeedableRng;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A seed typed in by a person, such as `"CrystalCaves42"` or `"1234"`.
+///
+/// Strings that are a decimal `u64` are used as the seed directly. Any other string is
+/// hashed with 64-bit FNV-1a over its UTF-8 bytes, with offset basis `0xcbf29ce484222325`
+/// and prime `0x100000001b3`. Both are fixed, so a seed string gives the same engine on every
+/// platform and crate version.
+///
+/// The seed displays as its numeric value, which parses back into the same seed.
+///
+/// ```rust
+/// use voxell_rng::prelude::*;
+/// use voxell_rng::rng::XorShift128;
+/// use voxell_rng::str_seed::StrSeed;
+///
+/// let seed: StrSeed = "CrystalCaves42".parse().unwrap();
+/// let mut rng = XorShift128::from_str_seed("CrystalCaves42");
+///
+/// // the numeric form is the same seed
+/// let mut same = XorShift128::from_str_seed(&seed.to_string());
+/// assert_eq!(rng.next_u64(), same.next_u64());
+/// ```
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct StrSeed(u64);
+
+impl StrSeed {
+    /// Create a seed from a string, see [`StrSeed`] for how it is interpreted.
+    #[inline]
+    #[must_use]
+    pub fn new(s: &str) -> Self {
+        s.parse().map_or_else(|_| Self(fnv1a_64(s.as_bytes())), Self)
+    }
+
+    /// The numeric value of the seed.
+    #[inline]
+    #[must_use]
+    pub const fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for StrSeed {
+    #[inline]
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<StrSeed> for u64 {
+    #[inline]
+    fn from(seed: StrSeed) -> Self {
+        seed.0
+    }
+}
+
+impl FromStr for StrSeed {
+    type Err = Infallible;
+
+    /// Every string is a valid seed, see [`StrSeed`].
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(s))
+    }
+}
+
+impl fmt::Display for StrSeed {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[inline]
+const fn fnv1a_64(bytes: &[u8]) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+        i += 1;
+    }
+    hash
+}
+
+/// Create any engine from a human-readable seed string.
+///
+/// Implemented for every [`SeedableRng`]. The string is turned into a [`StrSeed`] and passed to
+/// [`SeedableRng::seed_from_u64`].
+pub trait FromStrSeed: SeedableRng {
+    /// Create a new engine from a seed string, see [`StrSeed`] for how it is interpreted.
+    #[must_use]
+    fn from_str_seed(s: &str) -> Self;
+}
+
+impl<R: SeedableRng> FromStrSeed for R {
+    #[inline]
+    fn from_str_seed(s: &str) -> Self {
+        Self::seed_from_u64(StrSeed::new(s).value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fnv1a_64, FromStrSeed, StrSeed};
+    use crate::rng::XorShift128;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn fnv1a_matches_the_reference_vectors() {
+        assert_eq!(fnv1a_64(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a_64(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a_64(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn seeds_are_pinned() {
+        assert_eq!(StrSeed::new("hello").value(), 0xa430_d846_80aa_bd0b);
+        assert_eq!(StrSeed::new("CrystalCaves42").value(), 0x71b8_c5a0_b93d_068b);
+        assert_eq!(StrSeed::new("").value(), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(XorShift128::from_str_seed("hello"), XorShift128::seed_from_u64(0xa430_d846_80aa_bd0b));
+    }
+
+    #[test]
+    fn decimal_strings_are_used_directly() {
+        assert_eq!(StrSeed::new("42").value(), 42);
+        assert_eq!(StrSeed::new("0").value(), 0);
+        assert_eq!(StrSeed::new("18446744073709551615").value(), u64::MAX);
+        assert_eq!(XorShift128::from_str_seed("42"), XorShift128::seed_from_u64(42));
+
+        // anything that isn't a `u64` is hashed
+        assert_eq!(StrSeed::new("18446744073709551616").value(), fnv1a_64(b"18446744073709551616"));
+        assert_eq!(StrSeed::new("-1").value(), fnv1a_64(b"-1"));
+        assert_eq!(StrSeed::new(" 42").value(), fnv1a_64(b" 42"));
+    }
+}
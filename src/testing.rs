@@ -0,0 +1,321 @@
+use crate::entropy;
+use crate::rng::polyfill::polyfill_fill_bytes_u64;
+use crate::rng::SplitMix64;
+use crate::seed_sequence::SeedSequence;
+use rand_core::{RngCore, SeedableRng};
+use std::cell::Cell;
+use std::env;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+use std::thread;
+
+/// The environment variable the root seed is read from, in decimal or `0x` hexadecimal.
+pub const SEED_VAR: &str = "VOXELL_RNG_SEED";
+
+/// The most times [`check_shrinking`] runs a test to shrink one failing case.
+const MAX_SHRINK_RUNS: usize = 4096;
+
+/// Run `test` on `cases` independently seeded engines, and report the seed if a case fails.
+///
+/// Case `i` is seeded from the child `i` of a [`SeedSequence`] made from a 64-bit root seed, so
+/// any engine that implements [`SeedableRng`] works. The root seed is read from [`SEED_VAR`] if
+/// it is set, and drawn from the [`GlobalEntropy`](crate::entropy::GlobalEntropy) source
+/// otherwise. When a case panics, the root seed is printed to stderr before the panic carries
+/// on, and setting [`SEED_VAR`] to it runs the exact same cases again.
+///
+/// ```rust
+/// use voxell_rng::prelude::*;
+/// use voxell_rng::rng::Pcg32;
+/// use voxell_rng::testing;
+///
+/// testing::check(100, |rng: &mut Pcg32| {
+///     let roll = rng.next_bounded_u64(6) + 1;
+///     assert!((1..=6).contains(&roll));
+/// });
+/// ```
+///
+/// # Panics
+///
+/// This will panic if a case panics, or if [`SEED_VAR`] is set to something that isn't a `u64`.
+#[inline]
+#[track_caller]
+pub fn check<R, F>(cases: usize, mut test: F)
+where
+    R: SeedableRng,
+    F: FnMut(&mut R),
+{
+    let root = root_seed();
+    for case in 0..cases {
+        let _report = Report { root, case, cases };
+        test(&mut case_rng(root, case));
+    }
+}
+
+/// Same as [`check`], but when a case fails, its draws are shrunk to a smaller input that still
+/// fails before it is reported.
+///
+/// The test draws from a [`ShrinkRng`], which records the words the engine returns. A failing
+/// case is then run again on edited copies of those words: chunks are deleted and each word is
+/// lowered as far as it can go while the test keeps panicking. Inputs that are built from the
+/// draws, like lengths and bounded values, end up as short and as small as the bug allows.
+/// The shrunk words are printed and the test is run on them once more, so the panic that
+/// carries on is the one for the smallest input.
+///
+/// Shrinking catches the panics of the test, so it needs `panic = "unwind"`, and the test
+/// shouldn't keep state across runs that would make a panic stop or start happening.
+///
+/// ```rust,should_panic
+/// use voxell_rng::prelude::*;
+/// use voxell_rng::rng::XorShift128;
+/// use voxell_rng::testing::{self, ShrinkRng};
+///
+/// testing::check_shrinking(100, |rng: &mut ShrinkRng<XorShift128>| {
+///     let len = rng.next_bounded_usize(100);
+///     let items: Vec<u64> = (0..len).map(|_| rng.next_bounded_u64(1000)).collect();
+///     // fails for any list with an item of 500 or more, shrinks to a single item of 500
+///     assert!(items.iter().all(|&item| item < 500), "{items:?}");
+/// });
+/// ```
+///
+/// # Panics
+///
+/// This will panic if a case panics, or if [`SEED_VAR`] is set to something that isn't a `u64`.
+#[inline]
+#[track_caller]
+pub fn check_shrinking<R, F>(cases: usize, mut test: F)
+where
+    R: SeedableRng + RngCore,
+    F: FnMut(&mut ShrinkRng<R>),
+{
+    let root = root_seed();
+    for case in 0..cases {
+        let mut rng = ShrinkRng::new(case_rng(root, case));
+        let Err(payload) = run_quietly(|| test(&mut rng)) else {
+            continue;
+        };
+
+        let words = rng.drawn;
+        let drawn = words.len();
+        let shrunk = shrink(words, |candidate| {
+            let mut rng = ShrinkRng::replay(candidate.to_vec());
+            // a run that needed more words than the candidate has failed on the fallback's words
+            run_quietly(|| test(&mut rng))
+                .is_err()
+                .then_some(rng.next)
+                .filter(|&drawn| drawn <= candidate.len())
+        });
+        eprintln!(
+            "shrunk the draws of the failing case from {drawn} to {} words: {shrunk:#x?}",
+            shrunk.len()
+        );
+
+        let _report = Report { root, case, cases };
+        test(&mut ShrinkRng::replay(shrunk));
+        // the test only failed with the draws it originally made
+        panic::resume_unwind(payload);
+    }
+}
+
+/// The engine a [`check_shrinking`] test draws from.
+///
+/// Every call draws 64-bit words, the same way as the RNGs in [`mock`](crate::mock) do, so
+/// lowering a word lowers the value built from it. While a case first runs, the words come
+/// from the engine `R` and are recorded. While it is shrunk, they come from an edited copy of
+/// the record, and once that runs out they continue from `SplitMix64::wrap(0)`. A run that
+/// needs those extra words doesn't count as a smaller failing input.
+#[cfg_attr(not(feature = "strict"), derive(Clone))]
+#[derive(Debug)]
+pub struct ShrinkRng<R> {
+    engine: Option<R>,
+    drawn: Vec<u64>,
+    next: usize,
+    fallback: SplitMix64,
+}
+
+impl<R: RngCore> ShrinkRng<R> {
+    #[inline]
+    const fn new(engine: R) -> Self {
+        Self {
+            engine: Some(engine),
+            drawn: Vec::new(),
+            next: 0,
+            fallback: SplitMix64::wrap(0),
+        }
+    }
+
+    #[inline]
+    const fn replay(words: Vec<u64>) -> Self {
+        Self {
+            engine: None,
+            drawn: words,
+            next: 0,
+            fallback: SplitMix64::wrap(0),
+        }
+    }
+
+    /// The number of words drawn so far.
+    #[inline]
+    #[must_use]
+    pub const fn words_drawn(&self) -> usize {
+        self.next
+    }
+
+    #[inline]
+    fn word(&mut self) -> u64 {
+        self.next += 1;
+        if let Some(ref mut engine) = self.engine {
+            let word = engine.next_u64();
+            self.drawn.push(word);
+            return word;
+        }
+
+        match self.drawn.get(self.next - 1) {
+            Some(&word) => word,
+            None => self.fallback.next_u64(),
+        }
+    }
+}
+
+impl<R: RngCore> RngCore for ShrinkRng<R> {
+    /// Fill `dest` with the little-endian bytes of the next words.
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        polyfill_fill_bytes_u64(Self::word)(self, dest);
+    }
+
+    /// Return the low half of the next word.
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.word() as u32
+    }
+
+    /// Return the next word.
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.word()
+    }
+}
+
+/// Prints the root seed if it is dropped while a case panics.
+struct Report {
+    root: u64,
+    case: usize,
+    cases: usize,
+}
+
+impl Drop for Report {
+    #[inline]
+    fn drop(&mut self) {
+        if thread::panicking() {
+            eprintln!(
+                "case {} of {} failed, run again with {SEED_VAR}={:#x} to reproduce it",
+                self.case + 1,
+                self.cases,
+                self.root
+            );
+        }
+    }
+}
+
+#[inline]
+#[track_caller]
+fn root_seed() -> u64 {
+    let Ok(seed) = env::var(SEED_VAR) else {
+        return entropy::default_seed();
+    };
+
+    let seed = seed.trim();
+    let parsed = seed.strip_prefix("0x").map_or_else(|| seed.parse(), |hex| u64::from_str_radix(hex, 16));
+    parsed.unwrap_or_else(|_| panic!("{SEED_VAR} has to be a u64, got {seed:?}"))
+}
+
+#[inline]
+fn case_rng<R: SeedableRng>(root: u64, case: usize) -> R {
+    SeedSequence::with_spawn_key(&root, &(case as u64)).create_rng()
+}
+
+thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run `f`, catching its panic without printing it.
+#[inline]
+fn run_quietly(f: impl FnOnce()) -> thread::Result<()> {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.get() {
+                hook(info);
+            }
+        }));
+    });
+
+    QUIET.set(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    QUIET.set(false);
+    result
+}
+
+/// Shrink the failing `words`, `fails` runs the test on a candidate and returns the number of
+/// words it drew if it still fails.
+#[inline]
+fn shrink(mut words: Vec<u64>, mut fails: impl FnMut(&[u64]) -> Option<usize>) -> Vec<u64> {
+    let mut runs = 0;
+    let mut still_fails = |candidate: &mut Vec<u64>| {
+        if runs == MAX_SHRINK_RUNS {
+            return false;
+        }
+        runs += 1;
+        fails(candidate).is_some_and(|drawn| {
+            // the words past the last one drawn don't matter
+            candidate.truncate(drawn);
+            true
+        })
+    };
+
+    loop {
+        let before = words.clone();
+
+        for chunk in [8, 4, 2, 1] {
+            let mut start = 0;
+            while start + chunk <= words.len() {
+                let mut candidate = words.clone();
+                candidate.drain(start..start + chunk);
+                if still_fails(&mut candidate) {
+                    words = candidate;
+                } else {
+                    start += 1;
+                }
+            }
+        }
+
+        let mut index = 0;
+        while let Some(&word) = words.get(index) {
+            // binary search for the lowest value of this word that still fails
+            let (mut low, mut high) = (0, word);
+            while low < high {
+                let mid = low + (high - low) / 2;
+                let mut candidate = words.clone();
+                if let Some(slot) = candidate.get_mut(index) {
+                    *slot = mid;
+                }
+                if still_fails(&mut candidate) {
+                    high = mid;
+                    words = candidate;
+                } else {
+                    low = mid + 1;
+                }
+                if words.get(index) != Some(&high) {
+                    break;
+                }
+            }
+            index += 1;
+        }
+
+        // also stops once the runs are used up, since nothing changes after that
+        if words == before {
+            return words;
+        }
+    }
+}
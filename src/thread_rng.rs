@@ -0,0 +1,141 @@
+use crate::fork;
+use crate::prelude::{RngCoreExtension, ShuffleExt};
+use crate::rng::XorShift128;
+use crate::sample::{Random, SampleRange};
+use core::cell::RefCell;
+use core::marker::PhantomData;
+use rand_core::{RngCore, SeedableRng};
+
+struct ThreadRngState {
+    rng: Option<XorShift128>,
+    fork_generation: usize,
+    // set by `ThreadRng::set_seed`, a seeded generator is never replaced behind the caller's back
+    seeded: bool,
+}
+
+thread_local! {
+    static THREAD_RNG: RefCell<ThreadRngState> = const {
+        RefCell::new(ThreadRngState {
+            rng: None,
+            fork_generation: 0,
+            seeded: false,
+        })
+    };
+}
+
+/// Handle to a lazily seeded, thread-local [`XorShift128`].
+///
+/// Every thread gets its own generator, seeded from the
+/// [`GlobalEntropy`](crate::entropy::GlobalEntropy) source the first time it's used.
+/// After a `fork()`, the child reseeds on its next draw so it doesn't replay the parent's
+/// numbers. Handles are cheap to create, and all handles on the same thread share the
+/// same generator.
+///
+/// See [`thread_rng`].
+#[derive(Clone, Debug, Default)]
+pub struct ThreadRng {
+    // the handle refers to the current thread's generator, don't let it wander off
+    _not_send: PhantomData<*const ()>,
+}
+
+impl ThreadRng {
+    /// Replace the current thread's generator with one seeded from `seed`, so its output is
+    /// reproducible. The generator is not reseeded after a fork while the seed is in effect.
+    ///
+    /// Other threads are not affected.
+    #[inline]
+    pub fn set_seed(&self, seed: u64) {
+        THREAD_RNG.with_borrow_mut(|state| {
+            state.rng = Some(XorShift128::seed_from_u64(seed));
+            state.seeded = true;
+        });
+    }
+
+    /// Undo [`ThreadRng::set_seed`], the current thread's generator is seeded from the global
+    /// entropy source again on its next draw.
+    #[inline]
+    pub fn reseed(&self) {
+        THREAD_RNG.with_borrow_mut(|state| {
+            state.rng = None;
+            state.seeded = false;
+        });
+    }
+
+    #[inline]
+    fn with<T>(f: impl FnOnce(&mut XorShift128) -> T) -> T {
+        THREAD_RNG.with_borrow_mut(|state| {
+            let generation = fork::generation();
+            if !state.seeded && state.fork_generation != generation {
+                state.rng = None;
+            }
+            state.fork_generation = generation;
+
+            f(state.rng.get_or_insert_with(XorShift128::default))
+        })
+    }
+}
+
+impl RngCore for ThreadRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        Self::with(RngCore::next_u32)
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        Self::with(RngCore::next_u64)
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        Self::with(|rng| rng.fill_bytes(dst));
+    }
+}
+
+/// Get a handle to the current thread's generator.
+///
+/// # Panics
+///
+/// Drawing from the handle panics if the entropy source fails to provide the first seed and the
+/// fallback policy is [`EntropyFallback::Panic`](crate::entropy::EntropyFallback::Panic).
+#[inline]
+#[must_use]
+pub fn thread_rng() -> ThreadRng {
+    ThreadRng::default()
+}
+
+/// Generate a random value using the current thread's generator.
+///
+/// See [`Random`] for how each type is generated.
+#[inline]
+#[must_use = "please use the generated value"]
+pub fn random<T: Random>() -> T {
+    T::random(&mut thread_rng())
+}
+
+/// Generate a random value in `range` using the current thread's generator.
+///
+/// # Panics
+///
+/// Panics if the range is empty, or if it is a float range with an infinite bound.
+#[inline]
+#[must_use = "please use the generated value"]
+#[track_caller]
+pub fn random_range<T, S: SampleRange<T>>(range: S) -> T {
+    range.sample_single(&mut thread_rng())
+}
+
+/// Shuffle a slice in place using the current thread's generator.
+#[inline]
+pub fn shuffle<T>(slice: &mut [T]) {
+    slice.partial_shuffle(slice.len(), &mut thread_rng());
+}
+
+/// Select a random item from a slice using the current thread's generator.
+///
+/// - If the slice is empty, `None` is returned.
+#[inline]
+#[must_use]
+pub fn choose<T>(slice: &[T]) -> Option<&T> {
+    slice.get(thread_rng().next_bounded_usize(slice.len()))
+}
@@ -1,5 +1,52 @@
 use crate::rng::{SplitMix64, XoRoShiRo128, XorShift32};
-use std::time::{SystemTime, SystemTimeError, UNIX_EPOCH};
+use rand_core::SeedableRng;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Instant, SystemTime, SystemTimeError, UNIX_EPOCH};
+
+/// Seed any engine using the system time.
+///
+/// Implemented for every [`SeedableRng`]. The seed mixes the wall clock with a monotonic
+/// clock, the current thread's id and a process-wide counter, so two RNGs created in the same
+/// nanosecond, on the same or on different threads, still differ.
+///
+/// The seed is passed to [`SeedableRng::seed_from_u64`].
+pub trait TimeSeeded: Sized {
+    /// Create a new engine with a seed based on the system time.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the system time cannot be obtained.
+    fn time_seeded() -> Result<Self, SystemTimeError>;
+}
+
+impl<R: SeedableRng> TimeSeeded for R {
+    #[inline]
+    fn time_seeded() -> Result<Self, SystemTimeError> {
+        Ok(Self::seed_from_u64(time_seed()?))
+    }
+}
+
+/// A 64-bit seed mixed from the wall clock, a monotonic clock, the thread id and a counter.
+#[inline]
+pub(crate) fn time_seed() -> Result<u64, SystemTimeError> {
+    static ANCHOR: OnceLock<Instant> = OnceLock::new();
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let wall_clock = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
+    let monotonic = ANCHOR.get_or_init(Instant::now).elapsed().as_nanos();
+
+    let mut hasher = DefaultHasher::new();
+    thread::current().id().hash(&mut hasher);
+    let thread_id = hasher.finish();
+
+    let counter = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    let words = [wall_clock as u64, (wall_clock >> 64) as u64, monotonic as u64, thread_id, counter];
+    Ok(words.into_iter().fold(0, |seed, word| SplitMix64::wrap(seed ^ word).mix()))
+}
 
 /// uses the system time to seed an `XorShift32`
 ///
@@ -9,16 +56,14 @@ pub struct TimeSeededXorShift32;
 impl TimeSeededXorShift32 {
     /// Generates a new `XorShift32` with a seed based on the system time.
     ///
+    /// Same as [`TimeSeeded::time_seeded`].
+    ///
     /// # Errors
     ///
     /// This function will return an error if the system time cannot be obtained.
     #[inline]
     pub fn generate() -> Result<XorShift32, SystemTimeError> {
-        let current_time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
-        let mut temp_splitmix = SplitMix64::wrap(current_time as u64);
-        let seed = temp_splitmix.mix() as u32;
-
-        Ok(XorShift32::wrap(seed))
+        XorShift32::time_seeded()
     }
 }
 
@@ -30,13 +75,14 @@ pub struct TimeSeededSplitMix64;
 impl TimeSeededSplitMix64 {
     /// Generates a new `SplitMix64` with a seed based on the system time.
     ///
+    /// Same as [`TimeSeeded::time_seeded`].
+    ///
     /// # Errors
     ///
     /// This function will return an error if the system time cannot be obtained.
     #[inline]
     pub fn generate() -> Result<SplitMix64, SystemTimeError> {
-        let current_time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
-        Ok(SplitMix64::wrap(current_time as u64))
+        SplitMix64::time_seeded()
     }
 }
 
@@ -48,15 +94,13 @@ pub struct TimeSeededXoRoShiRo128Plus;
 impl TimeSeededXoRoShiRo128Plus {
     /// Generates a new `XoRoShiRo128Plus` with a seed based on the system time.
     ///
+    /// Same as [`TimeSeeded::time_seeded`].
+    ///
     /// # Errors
     ///
     /// This function will return an error if the system time cannot be obtained.
     #[inline]
     pub fn generate() -> Result<XoRoShiRo128, SystemTimeError> {
-        let current_time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
-        let mut temp_splitmix = SplitMix64::wrap(current_time as u64);
-        let seed = [temp_splitmix.mix(), temp_splitmix.mix()];
-
-        Ok(XoRoShiRo128::wrap(seed))
+        XoRoShiRo128::time_seeded()
     }
 }
@@ -0,0 +1,9 @@
+//! Compile errors of `#[derive(Random)]`, checked with `trybuild`.
+#![cfg(feature = "derive")]
+
+use trybuild::TestCases;
+
+#[test]
+fn invalid_attributes() {
+    TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,11 @@
+use voxell_rng::sample::Random;
+
+#[derive(Random)]
+enum Biome {
+    #[random(skip)]
+    Plains,
+    #[random(weight = 0)]
+    Forest,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use voxell_rng::sample::Random;
+
+#[derive(Random)]
+union Bits {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use voxell_rng::sample::Random;
+
+#[derive(Random)]
+struct Chunk {
+    #[random(weight = 3)]
+    height: i32,
+}
+
+fn main() {}
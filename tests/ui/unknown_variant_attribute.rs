@@ -0,0 +1,10 @@
+use voxell_rng::sample::Random;
+
+#[derive(Random)]
+enum Biome {
+    #[random(range = 0..10)]
+    Plains,
+    Forest,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use voxell_rng::sample::Random;
+
+#[derive(Random)]
+enum Biome {
+    #[random(weight = "3")]
+    Plains,
+    Forest,
+}
+
+fn main() {}
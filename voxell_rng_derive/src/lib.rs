@@ -0,0 +1,158 @@
+//! Derive macros for [`voxell_rng`](https://docs.rs/voxell_rng).
+//!
+//! Use them through the `derive` feature of `voxell_rng` rather than depending on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Error, Expr, Fields, LitInt, Result};
+
+/// Derive `voxell_rng::sample::Random` for a struct or an enum.
+///
+/// Fields are generated with their own `Random` impl, unless one of these attributes says otherwise:
+///
+/// - `#[random(range = 0..10)]` samples the field from the range with `SampleRange`.
+/// - `#[random(skip)]` sets the field to its `Default` value.
+///
+/// Enum variants are picked with equal probability, unless one of these attributes says otherwise:
+///
+/// - `#[random(weight = 3)]` makes the variant 3 times as likely as a variant with the default weight of 1.
+/// - `#[random(skip)]` never picks the variant, same as a weight of 0.
+#[proc_macro_derive(Random, attributes(random))]
+pub fn derive_random(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn expand(mut input: DeriveInput) -> Result<TokenStream2> {
+    let body = match input.data {
+        Data::Struct(ref data) => construct(&quote!(Self), &data.fields)?,
+        Data::Enum(ref data) => {
+            let mut variants = Vec::new();
+            for variant in &data.variants {
+                let weight = variant_weight(&variant.attrs)?;
+                if weight > 0 {
+                    let ident = &variant.ident;
+                    variants.push((weight, construct(&quote!(Self::#ident), &variant.fields)?));
+                }
+            }
+            choose_variant(&input, variants)?
+        }
+        Data::Union(_) => return Err(Error::new(input.span(), "`Random` can't be derived for unions")),
+    };
+
+    for param in input.generics.type_params_mut() {
+        param.bounds.push(parse_quote!(::voxell_rng::sample::Random));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    // named so it can't clash with a type parameter of the input, such as an `R`
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics ::voxell_rng::sample::Random for #name #ty_generics #where_clause {
+            #[inline]
+            fn random<__VoxellRng>(rng: &mut __VoxellRng) -> Self
+            where
+                __VoxellRng: ::voxell_rng::__private::RngCore + ?::core::marker::Sized,
+            {
+                #body
+            }
+        }
+    })
+}
+
+/// Pick a variant with a probability proportional to its weight.
+fn choose_variant(input: &DeriveInput, variants: Vec<(u64, TokenStream2)>) -> Result<TokenStream2> {
+    let total = variants.iter().try_fold(0_u64, |total, &(weight, _)| total.checked_add(weight));
+    let Some(total) = total else {
+        return Err(Error::new(input.span(), "the variant weights add up to more than `u64::MAX`"));
+    };
+
+    let mut variants = variants.into_iter();
+    let Some((_, last)) = variants.next_back() else {
+        return Err(Error::new(input.span(), "`Random` needs at least one variant that isn't skipped"));
+    };
+
+    let mut branches = TokenStream2::new();
+    let mut threshold = 0_u64;
+    for (weight, variant) in variants {
+        threshold += weight;
+        branches.extend(quote!(if choice < #threshold { #variant } else));
+    }
+
+    if branches.is_empty() {
+        return Ok(last);
+    }
+
+    Ok(quote! {
+        let choice = ::voxell_rng::sample::SampleRange::sample_single(0_u64..#total, rng);
+        #branches { #last }
+    })
+}
+
+/// An expression that builds `path` with every field generated according to its attributes.
+fn construct(path: &TokenStream2, fields: &Fields) -> Result<TokenStream2> {
+    let mut values = Vec::new();
+    for field in fields {
+        let ty = &field.ty;
+        let value = match field_kind(&field.attrs)? {
+            FieldKind::Random => quote_spanned!(ty.span()=> <#ty as ::voxell_rng::sample::Random>::random(rng)),
+            FieldKind::Range(range) => quote_spanned!(range.span()=> ::voxell_rng::sample::SampleRange::<#ty>::sample_single(#range, rng)),
+            FieldKind::Skip => quote_spanned!(ty.span()=> <#ty as ::core::default::Default>::default()),
+        };
+        values.push(value);
+    }
+
+    Ok(match *fields {
+        Fields::Named(ref named) => {
+            let names = named.named.iter().map(|field| &field.ident);
+            quote!(#path { #(#names: #values),* })
+        }
+        Fields::Unnamed(_) => quote!(#path(#(#values),*)),
+        Fields::Unit => quote!(#path),
+    })
+}
+
+enum FieldKind {
+    Random,
+    Range(Expr),
+    Skip,
+}
+
+fn field_kind(attrs: &[Attribute]) -> Result<FieldKind> {
+    let mut kind = FieldKind::Random;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("random")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("range") {
+                kind = FieldKind::Range(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                kind = FieldKind::Skip;
+                Ok(())
+            } else {
+                Err(meta.error("expected `range = ..` or `skip` on a field"))
+            }
+        })?;
+    }
+    Ok(kind)
+}
+
+fn variant_weight(attrs: &[Attribute]) -> Result<u64> {
+    let mut weight = 1;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("random")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("weight") {
+                weight = meta.value()?.parse::<LitInt>()?.base10_parse()?;
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                weight = 0;
+                Ok(())
+            } else {
+                Err(meta.error("expected `weight = ..` or `skip` on a variant"))
+            }
+        })?;
+    }
+    Ok(weight)
+}